@@ -0,0 +1,8 @@
+#![no_main]
+
+use leetify::MatchDetailsResponse;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    leetify_fuzz::check_json::<MatchDetailsResponse>(data);
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use leetify::{MatchDetailsResponse, ProfileResponse};
+use libfuzzer_sys::fuzz_target;
+
+// Consistent responses from the `arbitrary` feature must survive a
+// serialize/deserialize round trip unchanged
+fuzz_target!(|input: (ProfileResponse, MatchDetailsResponse)| {
+    let (profile, details) = input;
+    leetify_fuzz::check_round_trip(&profile);
+    leetify_fuzz::check_round_trip(&details);
+});
@@ -0,0 +1,20 @@
+//! Checks shared by the fuzz targets
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Deserialize arbitrary bytes; whatever parses must round-trip
+pub fn check_json<T: Serialize + DeserializeOwned>(data: &[u8]) {
+    if let Ok(parsed) = serde_json::from_slice::<T>(data) {
+        check_round_trip(&parsed);
+    }
+}
+
+/// Serializing, deserializing and serializing again must give the same JSON
+pub fn check_round_trip<T: Serialize + DeserializeOwned>(value: &T) {
+    let json = serde_json::to_value(value).expect("response types always serialize");
+    let reparsed: T = serde_json::from_value(json.clone()).expect("serialized JSON deserializes");
+    let rejson: Value = serde_json::to_value(&reparsed).unwrap();
+    assert_eq!(json, rejson);
+}
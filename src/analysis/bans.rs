@@ -0,0 +1,212 @@
+use crate::types::{MatchDetailsResponse, PlatformBanInfo, ProfileResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// When a ban was issued relative to a match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BanTiming {
+    /// The player was already banned when the match was played
+    BeforeMatch,
+    /// The player was banned after the match
+    AfterMatch,
+}
+
+/// A ban of a participant in one of the analysed matches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantBan {
+    pub steam64_id: String,
+    pub name: String,
+    pub team_number: u32,
+    /// Whether the banned player was on the analysed player's team
+    pub teammate: bool,
+    pub ban: PlatformBanInfo,
+    pub timing: BanTiming,
+}
+
+/// A match that contained at least one banned player
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BannedMatch {
+    pub match_id: String,
+    pub map_name: String,
+    pub finished_at: DateTime<Utc>,
+    /// Leetify's own flag for the match
+    pub has_banned_player: bool,
+    pub bans: Vec<ParticipantBan>,
+}
+
+impl BannedMatch {
+    /// Whether the match qualifies for "cheater detected" context: an
+    /// opponent was banned after the match was played
+    pub fn cheater_detected(&self) -> bool {
+        self.bans
+            .iter()
+            .any(|b| !b.teammate && b.timing == BanTiming::AfterMatch)
+    }
+}
+
+/// Matches of a player that contained banned players
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::{Client, Id};
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let client = Client::new();
+/// let player = client.player(Id::Steam64("76561198283431555".into()));
+///
+/// let report = player.ban_report().await?;
+/// for banned in report.cheater_detected() {
+///     println!("{} on {}: opponent banned afterwards", banned.match_id, banned.map_name);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BanReport {
+    pub steam64_id: String,
+    /// Number of matches the player took part in that were analysed
+    pub scanned_matches: usize,
+    /// Matches with banned participants, most recent first
+    pub matches: Vec<BannedMatch>,
+}
+
+impl BanReport {
+    /// Build a ban report for `steam64_id` from their matches and the
+    /// profiles of the other participants
+    ///
+    /// Participants without a profile in `profiles` are treated as unbanned.
+    pub fn new(
+        steam64_id: &str,
+        matches: &[MatchDetailsResponse],
+        profiles: &[ProfileResponse],
+    ) -> Self {
+        let mut scanned_matches = 0;
+        let mut banned_matches = Vec::new();
+
+        for details in matches {
+            let Some(own) = details.player_stats(steam64_id) else {
+                continue;
+            };
+            scanned_matches += 1;
+
+            let bans: Vec<ParticipantBan> = details
+                .stats
+                .iter()
+                .filter(|s| s.steam64_id != steam64_id)
+                .filter_map(|s| Some((s, profiles.iter().find(|p| p.steam64_id == s.steam64_id)?)))
+                .flat_map(|(stats, profile)| {
+                    profile.bans.iter().map(move |ban| ParticipantBan {
+                        steam64_id: stats.steam64_id.clone(),
+                        name: stats.name.clone(),
+                        team_number: stats.initial_team_number,
+                        teammate: stats.initial_team_number == own.initial_team_number,
+                        ban: ban.clone(),
+                        timing: if ban.banned_since <= details.finished_at {
+                            BanTiming::BeforeMatch
+                        } else {
+                            BanTiming::AfterMatch
+                        },
+                    })
+                })
+                .collect();
+
+            if !bans.is_empty() {
+                banned_matches.push(BannedMatch {
+                    match_id: details.id.clone(),
+                    map_name: details.map_name.clone(),
+                    finished_at: details.finished_at,
+                    has_banned_player: details.has_banned_player,
+                    bans,
+                });
+            }
+        }
+        banned_matches.sort_by_key(|m| std::cmp::Reverse(m.finished_at));
+
+        Self {
+            steam64_id: steam64_id.to_string(),
+            scanned_matches,
+            matches: banned_matches,
+        }
+    }
+
+    /// Matches eligible for "cheater detected" context
+    pub fn cheater_detected(&self) -> impl Iterator<Item = &BannedMatch> {
+        self.matches.iter().filter(|m| m.cheater_detected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PlayerStats;
+
+    fn at(day: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(day * 86_400, 0).unwrap()
+    }
+
+    fn line(id: &str, team: u32) -> PlayerStats {
+        PlayerStats {
+            steam64_id: id.to_string(),
+            initial_team_number: team,
+            ..Default::default()
+        }
+    }
+
+    fn banned(id: &str, day: i64) -> ProfileResponse {
+        ProfileResponse {
+            steam64_id: id.to_string(),
+            bans: vec![PlatformBanInfo {
+                platform: "steam".to_string(),
+                platform_nickname: id.to_string(),
+                banned_since: at(day),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_ban_report() {
+        let matches = [
+            MatchDetailsResponse {
+                id: "m1".to_string(),
+                finished_at: at(10),
+                has_banned_player: true,
+                stats: vec![line("me", 2), line("enemy", 3), line("mate", 2)],
+                ..Default::default()
+            },
+            MatchDetailsResponse {
+                id: "m2".to_string(),
+                finished_at: at(20),
+                stats: vec![line("me", 2), line("mate", 3)],
+                ..Default::default()
+            },
+            MatchDetailsResponse {
+                id: "m3".to_string(),
+                finished_at: at(30),
+                stats: vec![line("me", 2), line("clean", 3)],
+                ..Default::default()
+            },
+        ];
+        let profiles = [banned("enemy", 15), banned("mate", 5)];
+
+        let report = BanReport::new("me", &matches, &profiles);
+        assert_eq!(report.scanned_matches, 3);
+        assert_eq!(report.matches.len(), 2);
+
+        let m2 = &report.matches[0];
+        assert_eq!(m2.match_id, "m2");
+        assert_eq!(m2.bans[0].timing, BanTiming::BeforeMatch);
+        assert!(!m2.cheater_detected());
+
+        let m1 = &report.matches[1];
+        assert_eq!(m1.bans.len(), 2);
+        assert!(m1.bans.iter().any(|b| b.teammate));
+        assert!(m1.cheater_detected());
+
+        let detected: Vec<_> = report
+            .cheater_detected()
+            .map(|m| m.match_id.as_str())
+            .collect();
+        assert_eq!(detected, vec!["m1"]);
+    }
+}
@@ -0,0 +1,448 @@
+use super::Outcome;
+use crate::client::Client;
+use crate::error::Error;
+use crate::types::{Id, MatchDetailsResponse, ProfileResponse, Rating, Stats};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+/// Rating category players are compared on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Category {
+    Aim,
+    Utility,
+    Positioning,
+    Clutch,
+    Opening,
+}
+
+impl Category {
+    /// All categories
+    pub const ALL: [Category; 5] = [
+        Category::Aim,
+        Category::Utility,
+        Category::Positioning,
+        Category::Clutch,
+        Category::Opening,
+    ];
+
+    /// Get the rating of this category
+    pub fn value(&self, rating: &Rating) -> f64 {
+        match self {
+            Category::Aim => rating.aim,
+            Category::Utility => rating.utility,
+            Category::Positioning => rating.positioning,
+            Category::Clutch => rating.clutch,
+            Category::Opening => rating.opening,
+        }
+    }
+}
+
+/// A player taking part in a comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparedPlayer {
+    pub steam64_id: String,
+    pub name: String,
+    pub winrate: f64,
+    pub total_matches: u32,
+    pub rating: Rating,
+    pub stats: Stats,
+}
+
+/// One `Rating` or `Stats` field side by side for all compared players
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonRow {
+    /// Field name, e.g. `aim` or `reaction_time_ms`
+    pub metric: String,
+    /// Values in the order of [`Comparison::players`]
+    pub values: Vec<f64>,
+    /// Percentage difference of each value to the first player's value, or
+    /// `None` if the first player's value is zero
+    pub percent_difference: Vec<Option<f64>>,
+    /// Whether a higher value is better (false for e.g. reaction time)
+    pub higher_is_better: bool,
+    /// Index of the player with the best value
+    pub best: usize,
+}
+
+/// The best player in a rating category
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryWinner {
+    pub category: Category,
+    /// Steam64 ID of the winner
+    pub steam64_id: String,
+    pub value: f64,
+}
+
+/// A compared player's side of a match against another compared player
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncounterParticipant {
+    pub steam64_id: String,
+    pub team_number: u32,
+    pub outcome: Outcome,
+    pub leetify_rating: Option<f64>,
+}
+
+/// A match in which compared players played on opposing teams
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Encounter {
+    pub match_id: String,
+    pub map_name: String,
+    pub finished_at: DateTime<Utc>,
+    pub participants: Vec<EncounterParticipant>,
+}
+
+/// Side-by-side comparison of two or more players
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::{Client, Id};
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let client = Client::new();
+/// let comparison = client
+///     .compare([
+///         Id::Steam64("76561198283431555".into()),
+///         Id::Steam64("76561198000000000".into()),
+///     ])
+///     .await?;
+///
+/// for winner in &comparison.category_winners {
+///     println!("{:?}: {}", winner.category, winner.steam64_id);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comparison {
+    pub players: Vec<ComparedPlayer>,
+    /// One row per `Rating` field followed by one row per `Stats` field
+    pub rows: Vec<ComparisonRow>,
+    pub category_winners: Vec<CategoryWinner>,
+    /// Shared matches on opposing teams, most recent first
+    pub encounters: Vec<Encounter>,
+}
+
+impl Comparison {
+    /// Compare the given profiles
+    ///
+    /// `matches` may contain the match histories of any of the players;
+    /// duplicate matches are only counted once.
+    pub fn new(profiles: &[ProfileResponse], matches: &[MatchDetailsResponse]) -> Self {
+        let players: Vec<ComparedPlayer> = profiles
+            .iter()
+            .map(|p| ComparedPlayer {
+                steam64_id: p.steam64_id.clone(),
+                name: p.name.clone(),
+                winrate: p.winrate,
+                total_matches: p.total_matches,
+                rating: p.rating.clone(),
+                stats: p.stats.clone(),
+            })
+            .collect();
+
+        let mut rows = Vec::new();
+        if !players.is_empty() {
+            let ratings: Vec<_> = players.iter().map(|p| rating_fields(&p.rating)).collect();
+            let stats: Vec<_> = players.iter().map(|p| stats_fields(&p.stats)).collect();
+
+            for (i, &(metric, higher_is_better)) in RATING_FIELDS.iter().enumerate() {
+                let values = ratings.iter().map(|r| r[i]).collect();
+                rows.push(ComparisonRow::new(metric, values, higher_is_better));
+            }
+            for (i, &(metric, higher_is_better)) in STATS_FIELDS.iter().enumerate() {
+                let values = stats.iter().map(|s| s[i]).collect();
+                rows.push(ComparisonRow::new(metric, values, higher_is_better));
+            }
+        }
+
+        let category_winners = Category::ALL
+            .iter()
+            .filter_map(|&category| {
+                let winner = players.iter().max_by(|a, b| {
+                    category
+                        .value(&a.rating)
+                        .total_cmp(&category.value(&b.rating))
+                })?;
+                Some(CategoryWinner {
+                    category,
+                    steam64_id: winner.steam64_id.clone(),
+                    value: category.value(&winner.rating),
+                })
+            })
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut encounters: Vec<Encounter> = matches
+            .iter()
+            .filter(|m| seen.insert(m.id.as_str()))
+            .filter_map(|m| {
+                let participants: Vec<EncounterParticipant> = players
+                    .iter()
+                    .filter_map(|p| m.player_stats(&p.steam64_id))
+                    .map(|s| EncounterParticipant {
+                        steam64_id: s.steam64_id.clone(),
+                        team_number: s.initial_team_number,
+                        outcome: Outcome::from_rounds(s.rounds_won, s.rounds_lost),
+                        leetify_rating: s.leetify_rating,
+                    })
+                    .collect();
+
+                let opposed = participants
+                    .iter()
+                    .any(|a| participants.iter().any(|b| a.team_number != b.team_number));
+                opposed.then(|| Encounter {
+                    match_id: m.id.clone(),
+                    map_name: m.map_name.clone(),
+                    finished_at: m.finished_at,
+                    participants,
+                })
+            })
+            .collect();
+        encounters.sort_by_key(|e| Reverse(e.finished_at));
+
+        Self {
+            players,
+            rows,
+            category_winners,
+            encounters,
+        }
+    }
+
+    /// Get the row of a `Rating` or `Stats` field by name
+    pub fn row(&self, metric: &str) -> Option<&ComparisonRow> {
+        self.rows.iter().find(|r| r.metric == metric)
+    }
+
+    /// Head-to-head record of player `a` against player `b` as
+    /// `(wins, losses, ties)` from `a`'s perspective
+    pub fn head_to_head(&self, a: &str, b: &str) -> (u32, u32, u32) {
+        let mut record = (0, 0, 0);
+        for encounter in &self.encounters {
+            let find = |id: &str| encounter.participants.iter().find(|p| p.steam64_id == id);
+            let (Some(pa), Some(pb)) = (find(a), find(b)) else {
+                continue;
+            };
+            if pa.team_number == pb.team_number {
+                continue;
+            }
+            match pa.outcome {
+                Outcome::Win => record.0 += 1,
+                Outcome::Loss => record.1 += 1,
+                Outcome::Tie => record.2 += 1,
+            }
+        }
+        record
+    }
+}
+
+impl ComparisonRow {
+    fn new(metric: &str, values: Vec<f64>, higher_is_better: bool) -> Self {
+        let base = values[0];
+        let percent_difference = values
+            .iter()
+            .map(|v| (base != 0.0).then(|| (v - base) / base.abs() * 100.0))
+            .collect();
+        let best = (0..values.len())
+            .max_by(|&a, &b| {
+                let ordering = values[a].total_cmp(&values[b]);
+                if higher_is_better {
+                    ordering
+                } else {
+                    ordering.reverse()
+                }
+            })
+            .unwrap_or_default();
+
+        Self {
+            metric: metric.to_string(),
+            values,
+            percent_difference,
+            higher_is_better,
+            best,
+        }
+    }
+}
+
+/// `Rating` field names and whether higher is better
+const RATING_FIELDS: [(&str, bool); 7] = [
+    ("aim", true),
+    ("positioning", true),
+    ("utility", true),
+    ("clutch", true),
+    ("opening", true),
+    ("ct_leetify", true),
+    ("t_leetify", true),
+];
+
+fn rating_fields(r: &Rating) -> [f64; 7] {
+    [
+        r.aim,
+        r.positioning,
+        r.utility,
+        r.clutch,
+        r.opening,
+        r.ct_leetify,
+        r.t_leetify,
+    ]
+}
+
+/// `Stats` field names and whether higher is better
+const STATS_FIELDS: [(&str, bool); 21] = [
+    ("accuracy_enemy_spotted", true),
+    ("accuracy_head", true),
+    ("counter_strafing_good_shots_ratio", true),
+    ("ct_opening_aggression_success_rate", true),
+    ("ct_opening_duel_success_percentage", true),
+    ("flashbang_hit_foe_avg_duration", true),
+    ("flashbang_hit_foe_per_flashbang", true),
+    ("flashbang_hit_friend_per_flashbang", false),
+    ("flashbang_leading_to_kill", true),
+    ("flashbang_thrown", true),
+    ("he_foes_damage_avg", true),
+    ("he_friends_damage_avg", false),
+    ("preaim", false),
+    ("reaction_time_ms", false),
+    ("spray_accuracy", true),
+    ("t_opening_aggression_success_rate", true),
+    ("t_opening_duel_success_percentage", true),
+    ("traded_deaths_success_percentage", true),
+    ("trade_kill_opportunities_per_round", true),
+    ("trade_kills_success_percentage", true),
+    ("utility_on_death_avg", false),
+];
+
+fn stats_fields(s: &Stats) -> [f64; 21] {
+    [
+        s.accuracy_enemy_spotted,
+        s.accuracy_head,
+        s.counter_strafing_good_shots_ratio,
+        s.ct_opening_aggression_success_rate,
+        s.ct_opening_duel_success_percentage,
+        s.flashbang_hit_foe_avg_duration,
+        s.flashbang_hit_foe_per_flashbang,
+        s.flashbang_hit_friend_per_flashbang,
+        s.flashbang_leading_to_kill,
+        s.flashbang_thrown,
+        s.he_foes_damage_avg,
+        s.he_friends_damage_avg,
+        s.preaim,
+        s.reaction_time_ms,
+        s.spray_accuracy,
+        s.t_opening_aggression_success_rate,
+        s.t_opening_duel_success_percentage,
+        s.traded_deaths_success_percentage,
+        s.trade_kill_opportunities_per_round,
+        s.trade_kills_success_percentage,
+        s.utility_on_death_avg,
+    ]
+}
+
+impl Client {
+    /// Compare two or more players side by side
+    ///
+    /// Fetches the profile and match history of every player; shared
+    /// matches on opposing teams are reported as encounters.
+    ///
+    /// # Arguments
+    /// * `ids` - Player ids (Steam64 IDs or Leetify IDs)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Id};
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let comparison = client
+    ///     .compare(["76561198283431555", "76561198000000000"])
+    ///     .await?;
+    ///
+    /// if let Some(row) = comparison.row("reaction_time_ms") {
+    ///     println!("Fastest: {}", comparison.players[row.best].name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn compare<I>(&self, ids: impl IntoIterator<Item = I>) -> Result<Comparison, Error>
+    where
+        I: Into<Id>,
+    {
+        let mut profiles = Vec::new();
+        let mut matches = Vec::new();
+
+        for id in ids {
+            let id = id.into();
+            let (profile, history) =
+                tokio::try_join!(self.get_profile(id.clone()), self.get_profile_matches(id))?;
+            profiles.push(profile);
+            matches.extend(history);
+        }
+
+        Ok(Comparison::new(&profiles, &matches))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PlayerStats;
+
+    fn profile(id: &str, aim: f64, reaction_time_ms: f64) -> ProfileResponse {
+        ProfileResponse {
+            steam64_id: id.to_string(),
+            rating: Rating {
+                aim,
+                utility: 50.0,
+                ..Default::default()
+            },
+            stats: Stats {
+                reaction_time_ms,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn line(id: &str, team: u32, won: bool) -> PlayerStats {
+        PlayerStats {
+            steam64_id: id.to_string(),
+            initial_team_number: team,
+            rounds_won: if won { 13 } else { 7 },
+            rounds_lost: if won { 7 } else { 13 },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_comparison() {
+        let profiles = [profile("a", 80.0, 600.0), profile("b", 60.0, 500.0)];
+        let versus = MatchDetailsResponse {
+            id: "m1".to_string(),
+            stats: vec![line("a", 2, true), line("b", 3, false)],
+            ..Default::default()
+        };
+        let together = MatchDetailsResponse {
+            id: "m2".to_string(),
+            stats: vec![line("a", 2, true), line("b", 2, true)],
+            ..Default::default()
+        };
+        let matches = [versus.clone(), together, versus];
+
+        let comparison = Comparison::new(&profiles, &matches);
+
+        let aim = comparison.row("aim").unwrap();
+        assert_eq!(aim.values, vec![80.0, 60.0]);
+        assert_eq!(aim.percent_difference, vec![Some(0.0), Some(-25.0)]);
+        assert_eq!(aim.best, 0);
+        assert_eq!(comparison.row("reaction_time_ms").unwrap().best, 1);
+
+        let aim_winner = &comparison.category_winners[0];
+        assert_eq!(aim_winner.category, Category::Aim);
+        assert_eq!(aim_winner.steam64_id, "a");
+
+        assert_eq!(comparison.encounters.len(), 1);
+        assert_eq!(comparison.head_to_head("a", "b"), (1, 0, 0));
+        assert_eq!(comparison.head_to_head("b", "a"), (0, 1, 0));
+    }
+}
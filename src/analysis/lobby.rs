@@ -0,0 +1,327 @@
+use super::{fetch_profiles, mean};
+use crate::client::Client;
+use crate::error::Error;
+use crate::types::{MatchDetailsResponse, PlatformBanInfo, PlayerStats, ProfileResponse};
+use serde::{Deserialize, Serialize};
+
+/// Minimum, maximum and average of a value across a team
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Spread {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl Spread {
+    fn of(values: impl IntoIterator<Item = f64>) -> Option<Self> {
+        let values: Vec<f64> = values.into_iter().collect();
+        Some(Self {
+            min: values.iter().copied().reduce(f64::min)?,
+            max: values.iter().copied().reduce(f64::max)?,
+            mean: mean(values.iter().copied())?,
+        })
+    }
+
+    /// Difference between the highest and lowest value
+    pub fn range(&self) -> f64 {
+        self.max - self.min
+    }
+}
+
+/// Summary of one team in a match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamSummary {
+    pub team_number: u32,
+    /// Rounds won by the team
+    pub score: u32,
+    pub players: usize,
+    pub avg_leetify_rating: Option<f64>,
+    /// Spread of the in-game score
+    pub score_spread: Option<Spread>,
+    /// Spread of Premier ratings of players whose profile was fetched
+    pub premier_spread: Option<Spread>,
+    /// Spread of FACEIT Elo of players whose profile was fetched
+    pub faceit_elo_spread: Option<Spread>,
+}
+
+/// A participant with a ban on record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BannedParticipant {
+    pub steam64_id: String,
+    pub name: String,
+    pub team_number: u32,
+    pub bans: Vec<PlatformBanInfo>,
+}
+
+/// A participant who stood out in a specific area
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Standout {
+    pub steam64_id: String,
+    pub name: String,
+    pub team_number: u32,
+    /// The value the participant was selected on (kills, MVPs, ...)
+    pub value: f64,
+}
+
+impl Standout {
+    fn new(stats: &PlayerStats, value: f64) -> Self {
+        Self {
+            steam64_id: stats.steam64_id.clone(),
+            name: stats.name.clone(),
+            team_number: stats.initial_team_number,
+            value,
+        }
+    }
+}
+
+/// Structured overview of a single match lobby
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LobbyReport {
+    pub match_id: String,
+    pub map_name: String,
+    /// Teams in the order of the match's `team_scores`
+    pub teams: Vec<TeamSummary>,
+    /// Participants with bans, only filled when the match has a banned player
+    pub banned_players: Vec<BannedParticipant>,
+    /// Most kills, ties broken by damage
+    pub top_fragger: Option<Standout>,
+    /// Most MVPs, ties broken by Leetify rating
+    pub mvp: Option<Standout>,
+    /// Highest [`utility_impact`]
+    pub utility_player: Option<Standout>,
+    /// Steam64 IDs of participants whose profile was not available
+    pub missing_profiles: Vec<String>,
+}
+
+impl LobbyReport {
+    /// Build a lobby report from a match and the profiles of its participants
+    ///
+    /// Profiles are optional; participants without one are listed in
+    /// `missing_profiles` and left out of rank spreads and ban checks.
+    pub fn new(details: &MatchDetailsResponse, profiles: &[ProfileResponse]) -> Self {
+        let profile = |id: &str| profiles.iter().find(|p| p.steam64_id == id);
+
+        let teams = details
+            .team_scores
+            .iter()
+            .map(|team| {
+                let members: Vec<&PlayerStats> = details
+                    .stats
+                    .iter()
+                    .filter(|s| s.initial_team_number == team.team_number)
+                    .collect();
+                let member_profiles: Vec<&ProfileResponse> = members
+                    .iter()
+                    .filter_map(|s| profile(&s.steam64_id))
+                    .collect();
+
+                TeamSummary {
+                    team_number: team.team_number,
+                    score: team.score,
+                    players: members.len(),
+                    avg_leetify_rating: mean(members.iter().filter_map(|s| s.leetify_rating)),
+                    score_spread: Spread::of(members.iter().map(|s| s.score as f64)),
+                    premier_spread: Spread::of(
+                        member_profiles
+                            .iter()
+                            .filter_map(|p| p.ranks.premier)
+                            .map(f64::from),
+                    ),
+                    faceit_elo_spread: Spread::of(
+                        member_profiles
+                            .iter()
+                            .filter_map(|p| p.ranks.faceit_elo)
+                            .map(f64::from),
+                    ),
+                }
+            })
+            .collect();
+
+        let banned_players = if details.has_banned_player {
+            details
+                .stats
+                .iter()
+                .filter_map(|s| {
+                    let bans = &profile(&s.steam64_id)?.bans;
+                    (!bans.is_empty()).then(|| BannedParticipant {
+                        steam64_id: s.steam64_id.clone(),
+                        name: s.name.clone(),
+                        team_number: s.initial_team_number,
+                        bans: bans.clone(),
+                    })
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let top_fragger = details
+            .stats
+            .iter()
+            .max_by_key(|s| (s.total_kills, s.total_damage))
+            .map(|s| Standout::new(s, s.total_kills as f64));
+
+        let mvp = details
+            .stats
+            .iter()
+            .max_by(|a, b| {
+                a.mvps.cmp(&b.mvps).then(
+                    a.leetify_rating
+                        .unwrap_or(f64::MIN)
+                        .total_cmp(&b.leetify_rating.unwrap_or(f64::MIN)),
+                )
+            })
+            .map(|s| Standout::new(s, s.mvps as f64));
+
+        let utility_player = details
+            .stats
+            .iter()
+            .max_by(|a, b| utility_impact(a).total_cmp(&utility_impact(b)))
+            .map(|s| Standout::new(s, utility_impact(s)));
+
+        let missing_profiles = details
+            .stats
+            .iter()
+            .filter(|s| profile(&s.steam64_id).is_none())
+            .map(|s| s.steam64_id.clone())
+            .collect();
+
+        Self {
+            match_id: details.id.clone(),
+            map_name: details.map_name.clone(),
+            teams,
+            banned_players,
+            top_fragger,
+            mvp,
+            utility_player,
+            missing_profiles,
+        }
+    }
+}
+
+/// Weighted utility contribution of a participant in a match
+///
+/// Counts every enemy flashed, three points per flash assist and per flash
+/// leading to a kill, and one point per 20 HE damage dealt to enemies.
+pub fn utility_impact(stats: &PlayerStats) -> f64 {
+    stats.flashbang_hit_foe as f64
+        + 3.0 * stats.flash_assist as f64
+        + 3.0 * stats.flashbang_leading_to_kill as f64
+        + stats.he_foes_damage_avg * stats.he_thrown as f64 / 20.0
+}
+
+impl Client {
+    /// Build a lobby report for a match, fetching every participant's profile
+    ///
+    /// Participants whose profile cannot be found (e.g. private profiles)
+    /// are reported in `missing_profiles` rather than failing the report.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::Client;
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let details = client.get_match_by_game_id("match-id-123".to_string()).await?;
+    /// let lobby = client.lobby_report(&details).await?;
+    ///
+    /// for team in &lobby.teams {
+    ///     println!("Team {}: {:?} avg rating", team.team_number, team.avg_leetify_rating);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn lobby_report(&self, details: &MatchDetailsResponse) -> Result<LobbyReport, Error> {
+        let ids = details.stats.iter().map(|s| s.steam64_id.as_str());
+        let profiles = fetch_profiles(self, ids).await?;
+        Ok(LobbyReport::new(details, &profiles))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Ranks, TeamScore};
+
+    fn line(id: &str, team: u32, kills: u32, mvps: u32, flash_assist: u32) -> PlayerStats {
+        PlayerStats {
+            steam64_id: id.to_string(),
+            name: id.to_string(),
+            initial_team_number: team,
+            total_kills: kills,
+            mvps,
+            flash_assist,
+            score: kills * 2,
+            leetify_rating: Some(kills as f64 / 100.0),
+            ..Default::default()
+        }
+    }
+
+    fn profile(id: &str, premier: u32, banned: bool) -> ProfileResponse {
+        ProfileResponse {
+            steam64_id: id.to_string(),
+            ranks: Ranks {
+                premier: Some(premier),
+                ..Default::default()
+            },
+            bans: if banned {
+                vec![PlatformBanInfo::default()]
+            } else {
+                Vec::new()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_lobby_report() {
+        let details = MatchDetailsResponse {
+            id: "m1".to_string(),
+            has_banned_player: true,
+            team_scores: [
+                TeamScore {
+                    team_number: 2,
+                    score: 13,
+                },
+                TeamScore {
+                    team_number: 3,
+                    score: 9,
+                },
+            ],
+            stats: vec![
+                line("a", 2, 25, 3, 0),
+                line("b", 2, 15, 5, 1),
+                line("c", 3, 20, 2, 4),
+            ],
+            ..Default::default()
+        };
+        let profiles = [
+            profile("a", 15000, false),
+            profile("b", 12000, false),
+            profile("c", 9000, true),
+        ];
+
+        let report = LobbyReport::new(&details, &profiles);
+
+        let team = &report.teams[0];
+        assert_eq!((team.team_number, team.score, team.players), (2, 13, 2));
+        assert!((team.avg_leetify_rating.unwrap() - 0.2).abs() < 1e-9);
+        let premier = team.premier_spread.unwrap();
+        assert_eq!(
+            (premier.min, premier.max, premier.mean),
+            (12000.0, 15000.0, 13500.0)
+        );
+        assert_eq!(premier.range(), 3000.0);
+
+        assert_eq!(report.banned_players.len(), 1);
+        assert_eq!(report.banned_players[0].steam64_id, "c");
+        assert_eq!(report.top_fragger.unwrap().steam64_id, "a");
+        assert_eq!(report.mvp.unwrap().steam64_id, "b");
+        assert_eq!(report.utility_player.unwrap().steam64_id, "c");
+        assert!(report.missing_profiles.is_empty());
+
+        let report = LobbyReport::new(&details, &profiles[..2]);
+        assert_eq!(report.missing_profiles, vec!["c"]);
+        assert!(report.banned_players.is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+use super::{Confidence, Outcome, mean, ratio};
+use crate::types::{MatchDetailsResponse, Ranks};
+use serde::{Deserialize, Serialize};
+
+/// Aggregated performance of a player on a single map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapPerformance {
+    pub map_name: String,
+    pub matches: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+    /// Fraction of matches won (0.0 - 1.0)
+    pub win_rate: f64,
+    /// Total rounds won minus rounds lost over all matches
+    pub round_differential: i64,
+    /// Average Leetify rating, if any match on this map was rated
+    pub avg_leetify_rating: Option<f64>,
+    pub kd_ratio: f64,
+    /// Average damage per round
+    pub adr: f64,
+    /// Competitive (map-based) rank from the player's profile, if any
+    pub competitive_rank: Option<u32>,
+    pub confidence: Confidence,
+}
+
+impl MapPerformance {
+    /// Average round differential per match
+    pub fn avg_round_differential(&self) -> f64 {
+        self.round_differential as f64 / self.matches.max(1) as f64
+    }
+}
+
+/// Per-map performance breakdown for a single player
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::{Client, Id};
+/// # use leetify::analysis::MapReport;
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let client = Client::new();
+/// let id = Id::Steam64("76561198283431555".into());
+///
+/// let profile = client.get_profile(id.clone()).await?;
+/// let matches = client.get_profile_matches(id).await?;
+///
+/// let report = MapReport::new(&profile.steam64_id, &matches, &profile.ranks);
+/// for map in &report.maps {
+///     println!("{}: {:.0}% over {} matches", map.map_name, map.win_rate * 100.0, map.matches);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapReport {
+    pub steam64_id: String,
+    /// Maps ordered by number of matches played, most played first
+    pub maps: Vec<MapPerformance>,
+}
+
+impl MapReport {
+    /// Build a report for `steam64_id` from their match history and ranks
+    ///
+    /// Matches the player did not take part in are ignored.
+    pub fn new(steam64_id: &str, matches: &[MatchDetailsResponse], ranks: &Ranks) -> Self {
+        let mut maps: Vec<MapPerformance> = Vec::new();
+
+        let mut map_names: Vec<&str> = matches
+            .iter()
+            .filter(|m| m.player_stats(steam64_id).is_some())
+            .map(|m| m.map_name.as_str())
+            .collect();
+        map_names.sort_unstable();
+        map_names.dedup();
+
+        for map_name in map_names {
+            let lines: Vec<_> = matches
+                .iter()
+                .filter(|m| m.map_name == map_name)
+                .filter_map(|m| m.player_stats(steam64_id))
+                .collect();
+
+            let mut wins = 0;
+            let mut losses = 0;
+            let mut ties = 0;
+            for stats in &lines {
+                match Outcome::from_rounds(stats.rounds_won, stats.rounds_lost) {
+                    Outcome::Win => wins += 1,
+                    Outcome::Loss => losses += 1,
+                    Outcome::Tie => ties += 1,
+                }
+            }
+
+            let played = lines.len() as u32;
+            let kills = lines.iter().map(|s| s.total_kills).sum();
+            let deaths = lines.iter().map(|s| s.total_deaths).sum();
+            let damage = lines.iter().map(|s| s.total_damage).sum();
+            let rounds = lines.iter().map(|s| s.rounds_count).sum();
+
+            maps.push(MapPerformance {
+                map_name: map_name.to_string(),
+                matches: played,
+                wins,
+                losses,
+                ties,
+                win_rate: ratio(wins, played),
+                round_differential: lines
+                    .iter()
+                    .map(|s| s.rounds_won as i64 - s.rounds_lost as i64)
+                    .sum(),
+                avg_leetify_rating: mean(lines.iter().filter_map(|s| s.leetify_rating)),
+                kd_ratio: ratio(kills, deaths),
+                adr: ratio(damage, rounds),
+                competitive_rank: ranks
+                    .competitive
+                    .iter()
+                    .find(|r| r.map_name == map_name)
+                    .map(|r| r.rank),
+                confidence: Confidence::from_sample_size(lines.len()),
+            });
+        }
+
+        maps.sort_by(|a, b| b.matches.cmp(&a.matches).then(a.map_name.cmp(&b.map_name)));
+
+        Self {
+            steam64_id: steam64_id.to_string(),
+            maps,
+        }
+    }
+
+    /// Get the performance on a specific map
+    pub fn get(&self, map_name: &str) -> Option<&MapPerformance> {
+        self.maps.iter().find(|m| m.map_name == map_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CompetitiveRank, PlayerStats};
+
+    fn game(map: &str, won: u32, lost: u32, kills: u32, deaths: u32) -> MatchDetailsResponse {
+        MatchDetailsResponse {
+            map_name: map.to_string(),
+            stats: vec![PlayerStats {
+                steam64_id: "1".to_string(),
+                total_kills: kills,
+                total_deaths: deaths,
+                total_damage: (won + lost) * 80,
+                rounds_count: won + lost,
+                rounds_won: won,
+                rounds_lost: lost,
+                leetify_rating: Some(0.05),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_map_report() {
+        let matches = vec![
+            game("de_nuke", 13, 7, 20, 10),
+            game("de_nuke", 5, 13, 10, 20),
+            game("de_ancient", 13, 11, 15, 15),
+        ];
+        let ranks = Ranks {
+            competitive: vec![CompetitiveRank {
+                map_name: "de_ancient".to_string(),
+                rank: 12,
+            }],
+            ..Default::default()
+        };
+
+        let report = MapReport::new("1", &matches, &ranks);
+        assert_eq!(report.maps.len(), 2);
+
+        let nuke = &report.maps[0];
+        assert_eq!(nuke.map_name, "de_nuke");
+        assert_eq!((nuke.wins, nuke.losses, nuke.ties), (1, 1, 0));
+        assert_eq!(nuke.win_rate, 0.5);
+        assert_eq!(nuke.round_differential, -2);
+        assert_eq!(nuke.kd_ratio, 1.0);
+        assert_eq!(nuke.adr, 80.0);
+        assert_eq!(nuke.competitive_rank, None);
+        assert_eq!(nuke.confidence, Confidence::Low);
+
+        let ancient = report.get("de_ancient").unwrap();
+        assert_eq!(ancient.competitive_rank, Some(12));
+        assert_eq!(ancient.avg_leetify_rating, Some(0.05));
+    }
+
+    #[test]
+    fn test_map_report_ignores_other_players() {
+        let matches = vec![game("de_nuke", 13, 7, 20, 10)];
+        let report = MapReport::new("2", &matches, &Ranks::default());
+        assert!(report.maps.is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+//! Derived statistics computed from API responses
+//!
+//! Everything in this module works on data that has already been fetched,
+//! so the reports can be built from cached or stored responses as well as
+//! from live API calls.
+
+pub mod bans;
+pub mod comparison;
+pub mod lobby;
+pub mod maps;
+pub mod synergy;
+pub mod trends;
+
+pub use bans::{BanReport, BanTiming, BannedMatch, ParticipantBan};
+pub use comparison::{
+    Category, CategoryWinner, ComparedPlayer, Comparison, ComparisonRow, Encounter,
+    EncounterParticipant,
+};
+pub use lobby::{BannedParticipant, LobbyReport, Spread, Standout, TeamSummary};
+pub use maps::{MapPerformance, MapReport};
+pub use synergy::{Group, SynergyReport, TeammateSynergy};
+pub use trends::{Form, FormSample, Metric, Streak, TimePoint, Trend, TrendDirection};
+
+use crate::client::Client;
+use crate::error::Error;
+use crate::types::ProfileResponse;
+use serde::{Deserialize, Serialize};
+
+/// Result of a match from a single player's perspective
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Tie,
+}
+
+impl Outcome {
+    /// Determine the outcome from the rounds a player's team won and lost
+    pub fn from_rounds(won: u32, lost: u32) -> Self {
+        match won.cmp(&lost) {
+            std::cmp::Ordering::Greater => Outcome::Win,
+            std::cmp::Ordering::Less => Outcome::Loss,
+            std::cmp::Ordering::Equal => Outcome::Tie,
+        }
+    }
+
+    /// Parse the `outcome` field of a `RecentMatch` ("win", "loss" or "tie")
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "win" => Some(Outcome::Win),
+            "loss" => Some(Outcome::Loss),
+            "tie" => Some(Outcome::Tie),
+            _ => None,
+        }
+    }
+}
+
+/// How much a statistic can be trusted given the number of matches behind it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Confidence {
+    /// Fewer than 5 matches
+    Low,
+    /// 5 to 14 matches
+    Medium,
+    /// 15 or more matches
+    High,
+}
+
+impl Confidence {
+    /// Classify a sample size
+    pub fn from_sample_size(matches: usize) -> Self {
+        match matches {
+            0..5 => Confidence::Low,
+            5..15 => Confidence::Medium,
+            _ => Confidence::High,
+        }
+    }
+}
+
+/// Average of the values, or `None` if there are none
+pub(crate) fn mean(values: impl IntoIterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values
+        .into_iter()
+        .fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Divide, treating a zero denominator as one (as is customary for K/D)
+pub(crate) fn ratio(numerator: u32, denominator: u32) -> f64 {
+    numerator as f64 / denominator.max(1) as f64
+}
+
+/// Fetch the profiles of the given Steam64 IDs, once per ID
+///
+/// Profiles that cannot be found (404) or are private (403) are skipped;
+/// other errors, including responses that cannot be parsed, abort.
+pub(crate) async fn fetch_profiles<'a>(
+    client: &Client,
+    steam64_ids: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<ProfileResponse>, Error> {
+    let mut seen = std::collections::HashSet::new();
+    let mut profiles = Vec::new();
+
+    for steam64_id in steam64_ids {
+        if !seen.insert(steam64_id) {
+            continue;
+        }
+        match client.get_profile(steam64_id).await {
+            Ok(profile) => profiles.push(profile),
+            Err(Error::Api(403 | 404, _)) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(profiles)
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::mock::{MockFailure, MockServer, fixtures};
+
+    #[tokio::test]
+    async fn test_fetch_profiles() {
+        let mock = MockServer::seeded().await;
+        let client = Client::builder().base_url(mock.url()).build().unwrap();
+
+        let profiles = fetch_profiles(&client, [fixtures::STEAM64_ID, "1", fixtures::STEAM64_ID])
+            .await
+            .unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(mock.request_count(), 2);
+
+        mock.fail(MockFailure::MalformedJson);
+        assert!(matches!(
+            fetch_profiles(&client, [fixtures::STEAM64_ID]).await,
+            Err(Error::Api(200, _))
+        ));
+    }
+}
@@ -0,0 +1,289 @@
+use super::{Confidence, Outcome, mean, ratio};
+use crate::types::{MatchDetailsResponse, PlayerStats, RecentTeammate};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Minimum number of matches a group must have played together to be
+/// reported as a duo or stack
+const MIN_GROUP_MATCHES: u32 = 2;
+
+/// How a player performs with a specific teammate compared to without them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeammateSynergy {
+    pub steam64_id: String,
+    /// Most recent in-game name seen in the analysed matches
+    pub name: Option<String>,
+    /// Number of recent matches together, as reported by the profile
+    pub recent_matches_count: u32,
+    /// Number of analysed matches played on the same team
+    pub matches_together: u32,
+    pub wins_together: u32,
+    pub win_rate_with: f64,
+    /// Win rate in analysed matches without this teammate
+    pub win_rate_without: Option<f64>,
+    /// `win_rate_with - win_rate_without`
+    pub win_rate_delta: Option<f64>,
+    /// Player's own average Leetify rating with this teammate
+    pub avg_rating_with: Option<f64>,
+    /// Player's own average Leetify rating without this teammate
+    pub avg_rating_without: Option<f64>,
+    /// `avg_rating_with - avg_rating_without`
+    pub rating_delta: Option<f64>,
+    pub confidence: Confidence,
+}
+
+/// A group of players that repeatedly queued together
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Group {
+    /// Steam64 IDs of the teammates in the group, excluding the player
+    pub members: Vec<String>,
+    pub matches: u32,
+    pub wins: u32,
+    pub win_rate: f64,
+}
+
+/// Teammate synergy analysis for a single player
+///
+/// Teammates are taken from the profile's `recent_teammates` and joined with
+/// full match details, so only matches passed in are analysed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SynergyReport {
+    pub steam64_id: String,
+    /// Teammates ordered by number of matches played together
+    pub teammates: Vec<TeammateSynergy>,
+    /// Matches where exactly one recent teammate was on the player's team
+    pub duos: Vec<Group>,
+    /// Matches where two or more recent teammates were on the player's team
+    pub stacks: Vec<Group>,
+}
+
+impl SynergyReport {
+    /// Build a synergy report for `steam64_id`
+    pub fn new(
+        steam64_id: &str,
+        recent_teammates: &[RecentTeammate],
+        matches: &[MatchDetailsResponse],
+    ) -> Self {
+        // (player's own stats line, recent teammates on the same team)
+        let games: Vec<(&PlayerStats, Vec<&PlayerStats>)> = matches
+            .iter()
+            .filter_map(|m| {
+                let own = m.player_stats(steam64_id)?;
+                let mates = m
+                    .stats
+                    .iter()
+                    .filter(|s| {
+                        s.steam64_id != steam64_id
+                            && s.initial_team_number == own.initial_team_number
+                            && recent_teammates
+                                .iter()
+                                .any(|t| t.steam64_id == s.steam64_id)
+                    })
+                    .collect();
+                Some((own, mates))
+            })
+            .collect();
+
+        let won = |own: &PlayerStats| {
+            Outcome::from_rounds(own.rounds_won, own.rounds_lost) == Outcome::Win
+        };
+
+        let mut teammates: Vec<TeammateSynergy> = recent_teammates
+            .iter()
+            .map(|teammate| {
+                let (with, without): (Vec<_>, Vec<_>) = games.iter().partition(|(_, mates)| {
+                    mates.iter().any(|s| s.steam64_id == teammate.steam64_id)
+                });
+
+                let wins_with = with.iter().filter(|(own, _)| won(own)).count() as u32;
+                let wins_without = without.iter().filter(|(own, _)| won(own)).count() as u32;
+                let win_rate_with = ratio(wins_with, with.len() as u32);
+                let win_rate_without =
+                    (!without.is_empty()).then(|| ratio(wins_without, without.len() as u32));
+
+                let avg_rating_with = mean(with.iter().filter_map(|(own, _)| own.leetify_rating));
+                let avg_rating_without =
+                    mean(without.iter().filter_map(|(own, _)| own.leetify_rating));
+
+                TeammateSynergy {
+                    steam64_id: teammate.steam64_id.clone(),
+                    name: matches
+                        .iter()
+                        .filter(|m| m.player_stats(steam64_id).is_some())
+                        .filter_map(|m| {
+                            let mate = m.player_stats(&teammate.steam64_id)?;
+                            Some((m.finished_at, &mate.name))
+                        })
+                        .max_by_key(|(finished_at, _)| *finished_at)
+                        .map(|(_, name)| name.clone()),
+                    recent_matches_count: teammate.recent_matches_count,
+                    matches_together: with.len() as u32,
+                    wins_together: wins_with,
+                    win_rate_with,
+                    win_rate_without,
+                    win_rate_delta: win_rate_without
+                        .filter(|_| !with.is_empty())
+                        .map(|w| win_rate_with - w),
+                    avg_rating_with,
+                    avg_rating_without,
+                    rating_delta: avg_rating_with
+                        .zip(avg_rating_without)
+                        .map(|(w, wo)| w - wo),
+                    confidence: Confidence::from_sample_size(with.len()),
+                }
+            })
+            .collect();
+        teammates.sort_by_key(|t| Reverse(t.matches_together));
+
+        let mut groups: BTreeMap<Vec<String>, (u32, u32)> = BTreeMap::new();
+        for (own, mates) in &games {
+            if mates.is_empty() {
+                continue;
+            }
+            let mut members: Vec<String> = mates.iter().map(|s| s.steam64_id.clone()).collect();
+            members.sort_unstable();
+            let entry = groups.entry(members).or_default();
+            entry.0 += 1;
+            entry.1 += won(own) as u32;
+        }
+
+        let (mut duos, mut stacks): (Vec<Group>, Vec<Group>) = groups
+            .into_iter()
+            .filter(|(_, (matches, _))| *matches >= MIN_GROUP_MATCHES)
+            .map(|(members, (matches, wins))| Group {
+                members,
+                matches,
+                wins,
+                win_rate: ratio(wins, matches),
+            })
+            .partition(|g| g.members.len() == 1);
+        duos.sort_by_key(|g| Reverse(g.matches));
+        stacks.sort_by_key(|g| Reverse(g.matches));
+
+        Self {
+            steam64_id: steam64_id.to_string(),
+            teammates,
+            duos,
+            stacks,
+        }
+    }
+
+    /// Teammates the player performs best with, best first
+    ///
+    /// Only teammates with at least `min_matches` matches together are
+    /// considered. Ranked by win rate delta, then rating delta.
+    pub fn best_partners(&self, min_matches: u32) -> Vec<&TeammateSynergy> {
+        let mut partners: Vec<&TeammateSynergy> = self
+            .teammates
+            .iter()
+            .filter(|t| t.matches_together >= min_matches.max(1))
+            .collect();
+
+        partners.sort_by(|a, b| {
+            let key = |t: &TeammateSynergy| {
+                (
+                    t.win_rate_delta.unwrap_or(t.win_rate_with),
+                    t.rating_delta.unwrap_or(0.0),
+                )
+            };
+            let (a, b) = (key(a), key(b));
+            b.0.total_cmp(&a.0).then(b.1.total_cmp(&a.1))
+        });
+
+        partners
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(id: &str, team: u32, won: bool, rating: f64) -> PlayerStats {
+        PlayerStats {
+            steam64_id: id.to_string(),
+            name: format!("player-{id}"),
+            initial_team_number: team,
+            rounds_won: if won { 13 } else { 5 },
+            rounds_lost: if won { 5 } else { 13 },
+            leetify_rating: Some(rating),
+            ..Default::default()
+        }
+    }
+
+    fn game(lines: Vec<PlayerStats>) -> MatchDetailsResponse {
+        MatchDetailsResponse {
+            stats: lines,
+            ..Default::default()
+        }
+    }
+
+    fn teammate(id: &str) -> RecentTeammate {
+        RecentTeammate {
+            steam64_id: id.to_string(),
+            recent_matches_count: 3,
+        }
+    }
+
+    #[test]
+    fn test_synergy_report() {
+        let matches = vec![
+            game(vec![line("me", 2, true, 0.1), line("a", 2, true, 0.0)]),
+            game(vec![line("me", 2, true, 0.1), line("a", 2, true, 0.0)]),
+            game(vec![
+                line("me", 3, false, -0.1),
+                line("b", 3, false, 0.0),
+                line("a", 2, true, 0.0),
+            ]),
+            game(vec![line("me", 3, false, -0.1), line("b", 3, false, 0.0)]),
+        ];
+        let report = SynergyReport::new("me", &[teammate("a"), teammate("b")], &matches);
+
+        let a = &report.teammates[0];
+        assert_eq!(a.steam64_id, "a");
+        assert_eq!(a.matches_together, 2);
+        assert_eq!(a.win_rate_with, 1.0);
+        assert_eq!(a.win_rate_delta, Some(1.0));
+        assert_eq!(a.name.as_deref(), Some("player-a"));
+        assert!((a.rating_delta.unwrap() - 0.2).abs() < 1e-9);
+
+        assert_eq!(report.duos.len(), 2);
+        assert!(report.stacks.is_empty());
+
+        let best = report.best_partners(2);
+        assert_eq!(best[0].steam64_id, "a");
+        assert_eq!(best[1].steam64_id, "b");
+    }
+
+    #[test]
+    fn test_name_from_newest_match() {
+        let renamed = |name: &str, day: i64| {
+            let mut mate = line("a", 2, true, 0.0);
+            mate.name = name.to_string();
+            MatchDetailsResponse {
+                finished_at: chrono::DateTime::from_timestamp(day * 86_400, 0).unwrap(),
+                ..game(vec![line("me", 2, true, 0.0), mate])
+            }
+        };
+        let matches = vec![renamed("new", 2), renamed("old", 1)];
+        let report = SynergyReport::new("me", &[teammate("a")], &matches);
+        assert_eq!(report.teammates[0].name.as_deref(), Some("new"));
+    }
+
+    #[test]
+    fn test_stack_detection() {
+        let stack = || {
+            game(vec![
+                line("me", 2, true, 0.0),
+                line("a", 2, true, 0.0),
+                line("b", 2, true, 0.0),
+            ])
+        };
+        let report = SynergyReport::new("me", &[teammate("a"), teammate("b")], &[stack(), stack()]);
+
+        assert!(report.duos.is_empty());
+        assert_eq!(report.stacks.len(), 1);
+        assert_eq!(report.stacks[0].members, vec!["a", "b"]);
+        assert_eq!(report.stacks[0].win_rate, 1.0);
+    }
+}
@@ -0,0 +1,445 @@
+use super::{Outcome, mean};
+use crate::types::{MatchDetailsResponse, RecentMatch};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Two-tailed 95% critical values of Student's t distribution for 1 to 30
+/// degrees of freedom
+const T_CRITICAL_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+/// A single match reduced to the values that are tracked over time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormSample {
+    pub match_id: String,
+    pub finished_at: DateTime<Utc>,
+    pub outcome: Option<Outcome>,
+    pub leetify_rating: Option<f64>,
+    /// Accuracy when the enemy is spotted
+    pub aim: Option<f64>,
+    pub reaction_time_ms: Option<f64>,
+}
+
+impl FormSample {
+    /// Extract the sample of `steam64_id` from a match, if they played in it
+    pub fn from_match(details: &MatchDetailsResponse, steam64_id: &str) -> Option<Self> {
+        let stats = details.player_stats(steam64_id)?;
+        Some(Self {
+            match_id: details.id.clone(),
+            finished_at: details.finished_at,
+            outcome: Some(Outcome::from_rounds(stats.rounds_won, stats.rounds_lost)),
+            leetify_rating: stats.leetify_rating,
+            aim: Some(stats.accuracy_enemy_spotted),
+            reaction_time_ms: Some(stats.reaction_time * 1000.0),
+        })
+    }
+}
+
+impl From<&RecentMatch> for FormSample {
+    fn from(recent: &RecentMatch) -> Self {
+        Self {
+            match_id: recent.id.clone(),
+            finished_at: recent.finished_at,
+            outcome: Outcome::parse(&recent.outcome),
+            leetify_rating: Some(recent.leetify_rating),
+            aim: Some(recent.accuracy_enemy_spotted),
+            reaction_time_ms: Some(recent.reaction_time_ms as f64),
+        }
+    }
+}
+
+/// A value tracked over time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Metric {
+    LeetifyRating,
+    Aim,
+    ReactionTime,
+}
+
+impl Metric {
+    /// Get the value of this metric from a sample
+    pub fn value(&self, sample: &FormSample) -> Option<f64> {
+        match self {
+            Metric::LeetifyRating => sample.leetify_rating,
+            Metric::Aim => sample.aim,
+            Metric::ReactionTime => sample.reaction_time_ms,
+        }
+    }
+
+    /// Whether a higher value means better performance
+    pub fn higher_is_better(&self) -> bool {
+        !matches!(self, Metric::ReactionTime)
+    }
+}
+
+/// A metric value at a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimePoint {
+    pub finished_at: DateTime<Utc>,
+    pub value: f64,
+}
+
+/// Consecutive matches with the same outcome
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Streak {
+    pub outcome: Outcome,
+    pub length: usize,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+}
+
+/// Direction of a trend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TrendDirection {
+    Up,
+    Down,
+    /// No statistically significant change
+    Flat,
+}
+
+/// Linear trend of a metric over consecutive matches
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Trend {
+    pub metric: Metric,
+    /// Change of the metric per match
+    pub slope: f64,
+    /// Share of the variance explained by the trend line (0.0 - 1.0)
+    pub r_squared: f64,
+    /// t statistic of the slope
+    pub t_statistic: f64,
+    /// Whether the slope differs from zero at the 95% level
+    pub significant: bool,
+    pub direction: TrendDirection,
+    /// Number of matches the trend was computed from
+    pub samples: usize,
+}
+
+impl Trend {
+    /// Whether the player is getting better, taking into account whether
+    /// lower values are better for the metric (e.g. reaction time)
+    pub fn improving(&self) -> bool {
+        match self.direction {
+            TrendDirection::Up => self.metric.higher_is_better(),
+            TrendDirection::Down => !self.metric.higher_is_better(),
+            TrendDirection::Flat => false,
+        }
+    }
+}
+
+/// Time series of a player's matches, oldest first
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::{Client, Id};
+/// # use leetify::analysis::{Form, Metric};
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let client = Client::new();
+/// let profile = client.get_profile(Id::Steam64("76561198283431555".into())).await?;
+///
+/// let form = Form::from_recent_matches(&profile.recent_matches);
+/// if let Some(trend) = form.trend(Metric::LeetifyRating) {
+///     println!("Rating is trending {:?} ({:+.4} per match)", trend.direction, trend.slope);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Form {
+    samples: Vec<FormSample>,
+}
+
+impl Form {
+    /// Create a time series from samples in any order
+    pub fn new(samples: impl IntoIterator<Item = FormSample>) -> Self {
+        let mut samples: Vec<FormSample> = samples.into_iter().collect();
+        samples.sort_by_key(|s| s.finished_at);
+        Self { samples }
+    }
+
+    /// Create a time series from a profile's recent matches
+    pub fn from_recent_matches(matches: &[RecentMatch]) -> Self {
+        Self::new(matches.iter().map(FormSample::from))
+    }
+
+    /// Create a time series for `steam64_id` from full match details
+    pub fn from_matches(steam64_id: &str, matches: &[MatchDetailsResponse]) -> Self {
+        Self::new(
+            matches
+                .iter()
+                .filter_map(|m| FormSample::from_match(m, steam64_id)),
+        )
+    }
+
+    /// Get the samples, oldest first
+    pub fn samples(&self) -> &[FormSample] {
+        &self.samples
+    }
+
+    /// Average of the metric over the last `window` matches, for every match
+    /// that completes a full window
+    pub fn rolling_average(&self, metric: Metric, window: usize) -> Vec<TimePoint> {
+        let points = self.points(metric);
+        if window == 0 {
+            return Vec::new();
+        }
+
+        points
+            .windows(window)
+            .filter_map(|w| {
+                Some(TimePoint {
+                    finished_at: w.last()?.finished_at,
+                    value: mean(w.iter().map(|p| p.value))?,
+                })
+            })
+            .collect()
+    }
+
+    /// Average of the metric over the matches finished in the `days` days up
+    /// to and including each match
+    pub fn rolling_average_days(&self, metric: Metric, days: u32) -> Vec<TimePoint> {
+        let points = self.points(metric);
+        let span = TimeDelta::days(days as i64);
+
+        points
+            .iter()
+            .filter_map(|point| {
+                let since = point.finished_at - span;
+                Some(TimePoint {
+                    finished_at: point.finished_at,
+                    value: mean(
+                        points
+                            .iter()
+                            .filter(|p| p.finished_at > since && p.finished_at <= point.finished_at)
+                            .map(|p| p.value),
+                    )?,
+                })
+            })
+            .collect()
+    }
+
+    /// Exponential moving average of the metric
+    ///
+    /// Uses a smoothing factor of `2 / (span + 1)`, so recent matches weigh
+    /// roughly as much as a `span`-match simple average.
+    pub fn ema(&self, metric: Metric, span: usize) -> Vec<TimePoint> {
+        let alpha = 2.0 / (span.max(1) as f64 + 1.0);
+        let mut current: Option<f64> = None;
+
+        self.points(metric)
+            .into_iter()
+            .map(|point| {
+                let value = match current {
+                    Some(previous) => alpha * point.value + (1.0 - alpha) * previous,
+                    None => point.value,
+                };
+                current = Some(value);
+                TimePoint {
+                    finished_at: point.finished_at,
+                    value,
+                }
+            })
+            .collect()
+    }
+
+    /// All win, loss and tie streaks, oldest first
+    pub fn streaks(&self) -> Vec<Streak> {
+        let mut streaks: Vec<Streak> = Vec::new();
+
+        for sample in &self.samples {
+            let Some(outcome) = sample.outcome else {
+                continue;
+            };
+            match streaks.last_mut() {
+                Some(streak) if streak.outcome == outcome => {
+                    streak.length += 1;
+                    streak.ended_at = sample.finished_at;
+                }
+                _ => streaks.push(Streak {
+                    outcome,
+                    length: 1,
+                    started_at: sample.finished_at,
+                    ended_at: sample.finished_at,
+                }),
+            }
+        }
+
+        streaks
+    }
+
+    /// The streak the player is currently on
+    pub fn current_streak(&self) -> Option<Streak> {
+        self.streaks().pop()
+    }
+
+    /// The longest streak with the given outcome
+    pub fn longest_streak(&self, outcome: Outcome) -> Option<Streak> {
+        self.streaks()
+            .into_iter()
+            .filter(|s| s.outcome == outcome)
+            .max_by_key(|s| s.length)
+    }
+
+    /// Fit a least-squares line through the metric over consecutive matches
+    ///
+    /// Returns `None` if fewer than three matches have a value for the metric.
+    pub fn trend(&self, metric: Metric) -> Option<Trend> {
+        let values: Vec<f64> = self.points(metric).iter().map(|p| p.value).collect();
+        let n = values.len();
+        if n < 3 {
+            return None;
+        }
+
+        let x_mean = (n - 1) as f64 / 2.0;
+        let y_mean = mean(values.iter().copied())?;
+
+        let mut sxx = 0.0;
+        let mut sxy = 0.0;
+        let mut syy = 0.0;
+        for (i, y) in values.iter().enumerate() {
+            let dx = i as f64 - x_mean;
+            let dy = y - y_mean;
+            sxx += dx * dx;
+            sxy += dx * dy;
+            syy += dy * dy;
+        }
+
+        let slope = sxy / sxx;
+        let ss_residual = (syy - slope * sxy).max(0.0);
+        let r_squared = if syy > 0.0 {
+            1.0 - ss_residual / syy
+        } else {
+            0.0
+        };
+
+        let degrees_of_freedom = n - 2;
+        let standard_error = (ss_residual / degrees_of_freedom as f64 / sxx).sqrt();
+        let t_statistic = if standard_error > 0.0 {
+            slope / standard_error
+        } else if slope != 0.0 {
+            slope.signum() * f64::INFINITY
+        } else {
+            0.0
+        };
+
+        let critical = T_CRITICAL_95
+            .get(degrees_of_freedom - 1)
+            .copied()
+            .unwrap_or(1.96);
+        let significant = t_statistic.abs() > critical;
+
+        let direction = match (significant, slope > 0.0) {
+            (false, _) => TrendDirection::Flat,
+            (true, true) => TrendDirection::Up,
+            (true, false) => TrendDirection::Down,
+        };
+
+        Some(Trend {
+            metric,
+            slope,
+            r_squared,
+            t_statistic,
+            significant,
+            direction,
+            samples: n,
+        })
+    }
+
+    fn points(&self, metric: Metric) -> Vec<TimePoint> {
+        self.samples
+            .iter()
+            .filter_map(|s| {
+                Some(TimePoint {
+                    finished_at: s.finished_at,
+                    value: metric.value(s)?,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(ratings: &[f64], outcomes: &str) -> Form {
+        Form::new(
+            ratings
+                .iter()
+                .zip(outcomes.chars())
+                .enumerate()
+                .map(|(i, (rating, outcome))| FormSample {
+                    match_id: i.to_string(),
+                    finished_at: DateTime::from_timestamp(i as i64 * 86_400, 0).unwrap(),
+                    outcome: match outcome {
+                        'W' => Some(Outcome::Win),
+                        'L' => Some(Outcome::Loss),
+                        _ => Some(Outcome::Tie),
+                    },
+                    leetify_rating: Some(*rating),
+                    aim: None,
+                    reaction_time_ms: None,
+                }),
+        )
+    }
+
+    #[test]
+    fn test_rolling_averages() {
+        let form = form(&[1.0, 2.0, 3.0, 4.0], "WWLW");
+
+        let values: Vec<f64> = form
+            .rolling_average(Metric::LeetifyRating, 2)
+            .iter()
+            .map(|p| p.value)
+            .collect();
+        assert_eq!(values, vec![1.5, 2.5, 3.5]);
+
+        let values: Vec<f64> = form
+            .rolling_average_days(Metric::LeetifyRating, 3)
+            .iter()
+            .map(|p| p.value)
+            .collect();
+        assert_eq!(values, vec![1.0, 1.5, 2.0, 3.0]);
+
+        let ema = form.ema(Metric::LeetifyRating, 3);
+        assert_eq!(ema[0].value, 1.0);
+        assert_eq!(ema[1].value, 1.5);
+
+        assert!(form.rolling_average(Metric::Aim, 2).is_empty());
+    }
+
+    #[test]
+    fn test_streaks() {
+        let form = form(&[0.0; 6], "WWLWWW");
+
+        let streaks = form.streaks();
+        assert_eq!(streaks.len(), 3);
+        assert_eq!(form.current_streak().unwrap().length, 3);
+        assert_eq!(form.longest_streak(Outcome::Win).unwrap().length, 3);
+        assert_eq!(form.longest_streak(Outcome::Loss).unwrap().length, 1);
+        assert!(form.longest_streak(Outcome::Tie).is_none());
+    }
+
+    #[test]
+    fn test_trend() {
+        let rising = form(&[0.01, 0.02, 0.03, 0.04, 0.05], "WWWWW");
+        let trend = rising.trend(Metric::LeetifyRating).unwrap();
+        assert_eq!(trend.direction, TrendDirection::Up);
+        assert!(trend.improving());
+        assert!((trend.slope - 0.01).abs() < 1e-9);
+
+        let noisy = form(&[0.05, -0.02, 0.04, -0.01, 0.03, 0.0], "WLWLWL");
+        let trend = noisy.trend(Metric::LeetifyRating).unwrap();
+        assert_eq!(trend.direction, TrendDirection::Flat);
+        assert!(!trend.significant);
+
+        assert!(
+            form(&[0.1, 0.2], "WW")
+                .trend(Metric::LeetifyRating)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! [`Arbitrary`] impls for the response types, generating the same
+//! consistent values as the [`proptest`](crate::proptest) strategies
+
+use crate::generate::{self, Source};
+use crate::types::{
+    CompetitiveRank, MatchDetailsResponse, PlatformBanInfo, PlayerStats, ProfileResponse, Ranks,
+    Rating, RecentMatch, RecentTeammate, Stats, TeamScore,
+};
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+impl Source for Unstructured<'_> {
+    fn int(&mut self, lo: u32, hi: u32) -> u32 {
+        // Exhausted input yields the lower bound
+        self.int_in_range(lo..=hi).unwrap_or(lo)
+    }
+}
+
+macro_rules! arbitrary {
+    ($($name:ident => $ty:ty),* $(,)?) => {
+        $(
+            impl<'a> Arbitrary<'a> for $ty {
+                fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+                    Ok(generate::$name(u))
+                }
+            }
+        )*
+    };
+}
+
+arbitrary! {
+    profile => ProfileResponse,
+    ranks => Ranks,
+    competitive_rank => CompetitiveRank,
+    rating => Rating,
+    stats => Stats,
+    recent_match => RecentMatch,
+    recent_teammate => RecentTeammate,
+    platform_ban_info => PlatformBanInfo,
+    match_details => MatchDetailsResponse,
+    team_score => TeamScore,
+    player_stats => PlayerStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::checks;
+
+    #[test]
+    fn test_arbitrary_values_are_consistent() {
+        for seed in 0..50u32 {
+            let bytes: Vec<u8> = (0..4096u32)
+                .map(|i| (i.wrapping_mul(2_654_435_761) ^ seed.wrapping_mul(40_503)) as u8)
+                .collect();
+            let mut u = Unstructured::new(&bytes);
+            checks::match_details(&MatchDetailsResponse::arbitrary(&mut u).unwrap());
+            checks::profile(&ProfileResponse::arbitrary(&mut u).unwrap());
+            checks::player_stats(&PlayerStats::arbitrary(&mut u).unwrap());
+        }
+
+        // Empty input still produces valid values
+        let mut u = Unstructured::new(&[]);
+        checks::profile(&ProfileResponse::arbitrary(&mut u).unwrap());
+    }
+}
@@ -0,0 +1,409 @@
+//! Arrow record batches and Parquet files for large match datasets
+//!
+//! Matches become one row per match ([`matches_to_record_batch`]) or one row
+//! per participant ([`player_stats_to_record_batch`]). Times are UTC
+//! millisecond timestamp columns; data sources and map names are dictionary
+//! encoded.
+
+use crate::error::Error;
+use crate::types::{MatchDetailsResponse, PlayerStats};
+use ::arrow::array::{
+    ArrayRef, BooleanArray, DictionaryArray, Float64Array, StringArray, TimestampMillisecondArray,
+    UInt32Array,
+};
+use ::arrow::datatypes::{DataType, Field, Int32Type, Schema, SchemaRef, TimeUnit};
+use ::arrow::record_batch::RecordBatch;
+use ::parquet::arrow::ArrowWriter;
+use ::parquet::basic::Compression;
+use ::parquet::errors::ParquetError;
+use ::parquet::file::properties::WriterProperties;
+use std::io::Write;
+use std::sync::Arc;
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+}
+
+fn dictionary_type() -> DataType {
+    DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+}
+
+fn timestamps<'a>(matches: impl Iterator<Item = &'a MatchDetailsResponse>) -> ArrayRef {
+    Arc::new(
+        TimestampMillisecondArray::from_iter_values(
+            matches.map(|m| m.finished_at.timestamp_millis()),
+        )
+        .with_timezone("UTC"),
+    )
+}
+
+fn dictionary<'a>(values: impl Iterator<Item = &'a str>) -> ArrayRef {
+    Arc::new(values.collect::<DictionaryArray<Int32Type>>())
+}
+
+fn batch(columns: Vec<(Field, ArrayRef)>) -> Result<RecordBatch, Error> {
+    let (fields, arrays): (Vec<_>, Vec<_>) = columns.into_iter().unzip();
+    Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+}
+
+fn match_columns(matches: &[MatchDetailsResponse]) -> Vec<(Field, ArrayRef)> {
+    let team = |i: usize, score: bool| -> ArrayRef {
+        Arc::new(UInt32Array::from_iter_values(matches.iter().map(|m| {
+            let team = &m.team_scores[i];
+            if score { team.score } else { team.team_number }
+        })))
+    };
+
+    vec![
+        (
+            Field::new("match_id", DataType::Utf8, false),
+            Arc::new(StringArray::from_iter_values(matches.iter().map(|m| &m.id))),
+        ),
+        (
+            Field::new("finished_at", timestamp_type(), false),
+            timestamps(matches.iter()),
+        ),
+        (
+            Field::new("data_source", dictionary_type(), false),
+            dictionary(matches.iter().map(|m| m.data_source.as_str())),
+        ),
+        (
+            Field::new("data_source_match_id", DataType::Utf8, false),
+            Arc::new(StringArray::from_iter_values(
+                matches.iter().map(|m| &m.data_source_match_id),
+            )),
+        ),
+        (
+            Field::new("map_name", dictionary_type(), false),
+            dictionary(matches.iter().map(|m| m.map_name.as_str())),
+        ),
+        (
+            Field::new("has_banned_player", DataType::Boolean, false),
+            Arc::new(BooleanArray::from_iter(
+                matches.iter().map(|m| Some(m.has_banned_player)),
+            )),
+        ),
+        (
+            Field::new("team1_number", DataType::UInt32, false),
+            team(0, false),
+        ),
+        (
+            Field::new("team1_score", DataType::UInt32, false),
+            team(0, true),
+        ),
+        (
+            Field::new("team2_number", DataType::UInt32, false),
+            team(1, false),
+        ),
+        (
+            Field::new("team2_score", DataType::UInt32, false),
+            team(1, true),
+        ),
+    ]
+}
+
+macro_rules! stats_column {
+    ($rows:expr, $field:ident: text) => {
+        (
+            Field::new(stringify!($field), DataType::Utf8, false),
+            Arc::new(StringArray::from_iter_values(
+                $rows.iter().map(|(_, s)| s.$field.as_str()),
+            )) as ArrayRef,
+        )
+    };
+    ($rows:expr, $field:ident: uint) => {
+        (
+            Field::new(stringify!($field), DataType::UInt32, false),
+            Arc::new(UInt32Array::from_iter_values(
+                $rows.iter().map(|(_, s)| s.$field),
+            )) as ArrayRef,
+        )
+    };
+    ($rows:expr, $field:ident: float) => {
+        (
+            Field::new(stringify!($field), DataType::Float64, false),
+            Arc::new(Float64Array::from_iter_values(
+                $rows.iter().map(|(_, s)| s.$field),
+            )) as ArrayRef,
+        )
+    };
+    ($rows:expr, $field:ident: optional_float) => {
+        (
+            Field::new(stringify!($field), DataType::Float64, true),
+            Arc::new(Float64Array::from_iter($rows.iter().map(|(_, s)| s.$field))) as ArrayRef,
+        )
+    };
+}
+
+macro_rules! stats_columns {
+    ($rows:expr, { $($field:ident: $kind:ident),* $(,)? }) => {
+        vec![$(stats_column!($rows, $field: $kind)),*]
+    };
+}
+
+fn player_stats_columns(rows: &[(&MatchDetailsResponse, &PlayerStats)]) -> Vec<(Field, ArrayRef)> {
+    let mut columns = vec![
+        (
+            Field::new("match_id", DataType::Utf8, false),
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|(m, _)| &m.id),
+            )) as ArrayRef,
+        ),
+        (
+            Field::new("finished_at", timestamp_type(), false),
+            timestamps(rows.iter().map(|(m, _)| *m)),
+        ),
+        (
+            Field::new("data_source", dictionary_type(), false),
+            dictionary(rows.iter().map(|(m, _)| m.data_source.as_str())),
+        ),
+        (
+            Field::new("map_name", dictionary_type(), false),
+            dictionary(rows.iter().map(|(m, _)| m.map_name.as_str())),
+        ),
+    ];
+
+    columns.extend(stats_columns!(rows, {
+        steam64_id: text,
+        name: text,
+        initial_team_number: uint,
+        mvps: uint,
+        score: uint,
+        total_kills: uint,
+        total_deaths: uint,
+        total_assists: uint,
+        kd_ratio: float,
+        total_damage: uint,
+        dpr: float,
+        total_hs_kills: uint,
+        leetify_rating: optional_float,
+        ct_leetify_rating: optional_float,
+        t_leetify_rating: optional_float,
+        rounds_count: uint,
+        rounds_won: uint,
+        rounds_lost: uint,
+        rounds_survived: uint,
+        rounds_survived_percentage: float,
+        multi1k: uint,
+        multi2k: uint,
+        multi3k: uint,
+        multi4k: uint,
+        multi5k: uint,
+        preaim: float,
+        reaction_time: float,
+        accuracy: float,
+        accuracy_enemy_spotted: float,
+        accuracy_head: float,
+        spray_accuracy: float,
+        shots_fired: uint,
+        shots_fired_enemy_spotted: uint,
+        shots_hit_enemy_spotted: uint,
+        shots_hit_foe: uint,
+        shots_hit_foe_head: uint,
+        shots_hit_friend: uint,
+        shots_hit_friend_head: uint,
+        counter_strafing_shots_all: uint,
+        counter_strafing_shots_good: uint,
+        counter_strafing_shots_bad: uint,
+        counter_strafing_shots_good_ratio: float,
+        utility_on_death_avg: float,
+        he_thrown: uint,
+        he_foes_damage_avg: float,
+        he_friends_damage_avg: float,
+        molotov_thrown: uint,
+        smoke_thrown: uint,
+        flashbang_thrown: uint,
+        flashbang_hit_foe: uint,
+        flashbang_hit_foe_avg_duration: float,
+        flashbang_hit_friend: uint,
+        flashbang_leading_to_kill: uint,
+        flash_assist: uint,
+        trade_kill_opportunities: uint,
+        trade_kill_attempts: uint,
+        trade_kills_succeed: uint,
+        trade_kill_attempts_percentage: float,
+        trade_kills_success_percentage: float,
+        trade_kill_opportunities_per_round: float,
+        traded_death_opportunities: uint,
+        traded_death_attempts: uint,
+        traded_deaths_succeed: uint,
+        traded_death_attempts_percentage: float,
+        traded_deaths_success_percentage: float,
+        traded_deaths_opportunities_per_round: float,
+    }));
+
+    columns
+}
+
+/// Schema of [`matches_to_record_batch`]
+pub fn matches_schema() -> SchemaRef {
+    let fields: Vec<_> = match_columns(&[]).into_iter().map(|(f, _)| f).collect();
+    Arc::new(Schema::new(fields))
+}
+
+/// Schema of [`player_stats_to_record_batch`]
+pub fn player_stats_schema() -> SchemaRef {
+    let fields: Vec<_> = player_stats_columns(&[])
+        .into_iter()
+        .map(|(f, _)| f)
+        .collect();
+    Arc::new(Schema::new(fields))
+}
+
+/// Convert matches into a record batch with one row per match
+pub fn matches_to_record_batch(matches: &[MatchDetailsResponse]) -> Result<RecordBatch, Error> {
+    batch(match_columns(matches))
+}
+
+/// Convert matches into a record batch with one row per participant,
+/// carrying the match ID, finish time, data source and map of its match
+pub fn player_stats_to_record_batch(
+    matches: &[MatchDetailsResponse],
+) -> Result<RecordBatch, Error> {
+    let rows: Vec<_> = matches
+        .iter()
+        .flat_map(|m| m.stats.iter().map(move |s| (m, s)))
+        .collect();
+    batch(player_stats_columns(&rows))
+}
+
+/// Write record batches sharing one schema to a Snappy-compressed Parquet
+/// file
+///
+/// The schema is taken from the first batch, so at least one is required;
+/// pass an empty batch (e.g. from an empty match list) to write a file with
+/// the schema and no rows. An empty slice is an error and writes nothing.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::{Client, Id};
+/// # use leetify::arrow::{player_stats_to_record_batch, write_parquet};
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let client = Client::new();
+/// let matches = client
+///     .get_profile_matches(Id::Steam64("76561198283431555".into()))
+///     .await?;
+///
+/// let batch = player_stats_to_record_batch(&matches)?;
+/// write_parquet(std::fs::File::create("player_stats.parquet")?, &[batch])?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_parquet<W: Write + Send>(writer: W, batches: &[RecordBatch]) -> Result<(), Error> {
+    let Some(first) = batches.first() else {
+        return Err(ParquetError::General(
+            "at least one record batch is needed for the schema".to_string(),
+        )
+        .into());
+    };
+
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut parquet = ArrowWriter::try_new(writer, first.schema(), Some(properties))?;
+    for batch in batches {
+        parquet.write(batch)?;
+    }
+    parquet.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::arrow::array::Array;
+    use ::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use chrono::DateTime;
+
+    fn game(id: &str, map: &str) -> MatchDetailsResponse {
+        MatchDetailsResponse {
+            id: id.to_string(),
+            finished_at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            data_source: "faceit".to_string(),
+            map_name: map.to_string(),
+            stats: vec![
+                PlayerStats {
+                    steam64_id: "a".to_string(),
+                    leetify_rating: Some(0.05),
+                    ..Default::default()
+                },
+                PlayerStats {
+                    steam64_id: "b".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_batches() {
+        let matches = vec![game("m1", "de_nuke"), game("m2", "de_nuke")];
+
+        let batch = matches_to_record_batch(&matches).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.schema(), matches_schema());
+        assert_eq!(batch.schema().field(1).data_type(), &timestamp_type());
+
+        let batch = player_stats_to_record_batch(&matches).unwrap();
+        assert_eq!(batch.num_rows(), 4);
+        assert_eq!(batch.schema(), player_stats_schema());
+
+        let maps = batch
+            .column_by_name("map_name")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<DictionaryArray<Int32Type>>()
+            .unwrap();
+        assert_eq!(maps.values().len(), 1);
+
+        let ratings = batch.column_by_name("leetify_rating").unwrap();
+        assert_eq!(ratings.null_count(), 2);
+    }
+
+    #[test]
+    fn test_parquet_without_rows() {
+        let mut buffer = Vec::new();
+        assert!(matches!(
+            write_parquet(&mut buffer, &[]),
+            Err(Error::Parquet(_))
+        ));
+        assert!(buffer.is_empty());
+
+        let path =
+            std::env::temp_dir().join(format!("leetify-{}-empty.parquet", std::process::id()));
+        let batch = player_stats_to_record_batch(&[]).unwrap();
+        write_parquet(
+            std::fs::File::create(&path).unwrap(),
+            std::slice::from_ref(&batch),
+        )
+        .unwrap();
+        let reader =
+            ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reader.schema(), &player_stats_schema());
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 0);
+    }
+
+    #[test]
+    fn test_parquet_round_trip() {
+        let path = std::env::temp_dir().join(format!("leetify-{}.parquet", std::process::id()));
+        let batch = player_stats_to_record_batch(&[game("m1", "de_nuke")]).unwrap();
+        write_parquet(
+            std::fs::File::create(&path).unwrap(),
+            std::slice::from_ref(&batch),
+        )
+        .unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<_> = reader.map(Result::unwrap).collect();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0], batch);
+    }
+}
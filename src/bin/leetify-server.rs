@@ -0,0 +1,91 @@
+//! Caching gateway that shares one Leetify API key between internal services
+
+use clap::Parser;
+use leetify::Client;
+use leetify::server::Gateway;
+use std::net::SocketAddr;
+use std::process::ExitCode;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(
+    name = "leetify-server",
+    version,
+    about = "Serve the Leetify API through a shared caching gateway"
+)]
+struct Args {
+    /// Address to listen on
+    #[arg(long, env = "LEETIFY_LISTEN", default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+
+    /// API key used for all upstream requests
+    #[arg(long, env = "LEETIFY_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+
+    /// Base URL of the API
+    #[arg(long, env = "LEETIFY_BASE_URL")]
+    base_url: Option<String>,
+
+    /// Seconds to cache profiles and match histories
+    #[arg(long, env = "LEETIFY_TTL", default_value_t = 60)]
+    ttl: u64,
+
+    /// Seconds to cache match details
+    #[arg(long, env = "LEETIFY_MATCH_TTL", default_value_t = 86400)]
+    match_ttl: u64,
+
+    /// Upper bound for upstream requests per second
+    #[arg(long, env = "LEETIFY_RATE", default_value_t = 10.0, value_parser = positive_rate)]
+    rate: f64,
+}
+
+/// Parse a finite, positive number of requests per second
+fn positive_rate(value: &str) -> Result<f64, String> {
+    let rate: f64 = value.parse().map_err(|e| format!("{}", e))?;
+    if rate > 0.0 && rate.is_finite() {
+        Ok(rate)
+    } else {
+        Err("must be a positive number".to_string())
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let mut builder = Client::builder();
+    if let Some(key) = args.api_key {
+        builder = builder.api_key(key);
+    }
+    if let Some(url) = args.base_url {
+        builder = builder.base_url(url);
+    }
+    let client = match builder.build() {
+        Ok(client) => client,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let gateway = Gateway::builder(client)
+        .ttl(Duration::from_secs(args.ttl))
+        .match_ttl(Duration::from_secs(args.match_ttl))
+        .requests_per_second(args.rate)
+        .build();
+
+    let listener = match tokio::net::TcpListener::bind(args.listen).await {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("error: cannot listen on {}: {}", args.listen, error);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("listening on {}", args.listen);
+
+    if let Err(error) = axum::serve(listener, gateway.router()).await {
+        eprintln!("error: {}", error);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,371 @@
+//! Command-line interface for the Leetify API
+
+use clap::{Parser, Subcommand, ValueEnum};
+use leetify::export::{self, Format};
+use leetify::scoreboard::{Scoreboard, Style};
+use leetify::{Client, DataSource, Error, Id, MatchDetailsResponse, ProfileResponse};
+use serde::Deserialize;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "leetify", version, about = "Query the Leetify public API")]
+struct Cli {
+    /// API key (overrides the config file)
+    #[arg(long, env = "LEETIFY_API_KEY", hide_env_values = true, global = true)]
+    api_key: Option<String>,
+
+    /// Config file [default: $XDG_CONFIG_HOME/leetify/config.toml]
+    #[arg(long, env = "LEETIFY_CONFIG", global = true)]
+    config: Option<PathBuf>,
+
+    /// Base URL of the API
+    #[arg(long, global = true)]
+    base_url: Option<String>,
+
+    /// Output format
+    #[arg(long, short, value_enum, default_value_t = Output::Table, global = true)]
+    output: Output,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show a player's profile
+    Profile {
+        /// Steam64 ID or Leetify ID
+        id: String,
+    },
+    /// Show a player's match history
+    Matches {
+        /// Steam64 ID or Leetify ID
+        id: String,
+    },
+    /// Show the details of a match
+    Match {
+        /// Leetify match ID, or the data source's match ID with --source
+        id: String,
+        /// Look the match up by data source, e.g. faceit or matchmaking
+        #[arg(long)]
+        source: Option<String>,
+        /// Steam64 ID of a player to emphasize in the scoreboard
+        #[arg(long)]
+        highlight: Option<String>,
+    },
+    /// Check that the API key is valid
+    ValidateKey,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    Table,
+    Json,
+    Csv,
+}
+
+/// Settings read from the config file
+#[derive(Debug, Default, PartialEq, Deserialize)]
+struct Config {
+    api_key: Option<String>,
+    base_url: Option<String>,
+}
+
+impl Config {
+    /// Read the config file; a missing default config file is not an error
+    fn load(path: Option<&Path>) -> Result<Self, String> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match default_config_path() {
+                Some(path) => (path, false),
+                None => return Ok(Config::default()),
+            },
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if !required && e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+}
+
+fn default_config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("leetify").join("config.toml"))
+}
+
+/// Exit code for a failed request
+fn exit_code(error: &Error) -> u8 {
+    match error {
+        Error::InvalidApiKey => 3,
+        Error::RateLimited(_) => 4,
+        Error::Api(..) => 5,
+        Error::ServerError => 6,
+        Error::Http(_) => 7,
+        Error::Json(_) => 8,
+        Error::MissingParameter(_) | Error::InvalidGameId(_) | Error::InvalidDataSource(_) => 2,
+        _ => 1,
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let config = match Config::load(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("error: invalid config file {}", message);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(cli, config).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::from(exit_code(&error))
+        }
+    }
+}
+
+async fn run(cli: Cli, config: Config) -> Result<(), Error> {
+    let mut builder = Client::builder();
+    if let Some(key) = cli.api_key.or(config.api_key) {
+        builder = builder.api_key(key);
+    }
+    if let Some(url) = cli.base_url.or(config.base_url) {
+        builder = builder.base_url(url);
+    }
+    let client = builder.build()?;
+    let mut out = io::stdout().lock();
+
+    match cli.command {
+        Command::Profile { id } => {
+            let profile = client.get_profile(Id::from(id)).await?;
+            match cli.output {
+                Output::Table => print_profile(&mut out, &profile)?,
+                Output::Json => print_json(&mut out, &profile)?,
+                Output::Csv => export::write_profiles(&mut out, [&profile], Format::Csv)?,
+            }
+        }
+        Command::Matches { id } => {
+            let id = Id::from(id);
+            let matches = client.get_profile_matches(id.clone()).await?;
+            match cli.output {
+                Output::Table => {
+                    // Stats lines only carry Steam64 IDs
+                    let steam64_id = client.resolve_steam64_id(id).await?;
+                    print_matches(&mut out, steam64_id.as_ref(), &matches)?
+                }
+                Output::Json => print_json(&mut out, &matches)?,
+                Output::Csv => export::write_matches(&mut out, &matches, Format::Csv)?,
+            }
+        }
+        Command::Match {
+            id,
+            source,
+            highlight,
+        } => {
+            let details = match source {
+                Some(source) => {
+                    client
+                        .get_match_by_data_source(DataSource::from(source), id)
+                        .await?
+                }
+                None => client.get_match_by_game_id(id).await?,
+            };
+            match cli.output {
+                Output::Table => print_match(&mut out, &details, highlight)?,
+                Output::Json => print_json(&mut out, &details)?,
+                Output::Csv => export::write_matches(&mut out, [&details], Format::Csv)?,
+            }
+        }
+        Command::ValidateKey => {
+            client.validate_api_key().await?;
+            if cli.output == Output::Json {
+                print_json(&mut out, &serde_json::json!({ "valid": true }))?;
+            } else {
+                writeln!(out, "API key is valid")?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_json(out: &mut impl Write, value: &impl serde::Serialize) -> Result<(), Error> {
+    serde_json::to_writer_pretty(&mut *out, value)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Print rows as left-aligned columns
+fn print_table(out: &mut impl Write, header: &[&str], rows: &[Vec<String>]) -> io::Result<()> {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: &mut dyn Iterator<Item = &str>| {
+        let padded: Vec<_> = cells
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    };
+
+    writeln!(out, "{}", line(&mut header.iter().copied()))?;
+    for row in rows {
+        writeln!(out, "{}", line(&mut row.iter().map(String::as_str)))?;
+    }
+    Ok(())
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+fn print_profile(out: &mut impl Write, profile: &ProfileResponse) -> io::Result<()> {
+    let bans: Vec<_> = profile.bans.iter().map(|b| b.platform.as_str()).collect();
+    let rows = [
+        ("Name", profile.name.clone()),
+        ("Steam64 ID", profile.steam64_id.clone()),
+        ("Leetify ID", optional(profile.id.as_ref())),
+        ("Matches", profile.total_matches.to_string()),
+        ("Win rate", format!("{:.1}%", profile.winrate * 100.0)),
+        (
+            "Leetify",
+            optional(profile.ranks.leetify.map(|r| format!("{:+.2}", r))),
+        ),
+        ("Premier", optional(profile.ranks.premier)),
+        ("FACEIT", optional(profile.ranks.faceit)),
+        ("FACEIT Elo", optional(profile.ranks.faceit_elo)),
+        ("Aim", format!("{:.1}", profile.rating.aim)),
+        ("Positioning", format!("{:.1}", profile.rating.positioning)),
+        ("Utility", format!("{:.1}", profile.rating.utility)),
+        ("Clutch", format!("{:.1}", profile.rating.clutch)),
+        ("Opening", format!("{:.1}", profile.rating.opening)),
+        (
+            "Bans",
+            if bans.is_empty() {
+                "none".to_string()
+            } else {
+                bans.join(", ")
+            },
+        ),
+    ];
+
+    let rows: Vec<_> = rows
+        .into_iter()
+        .map(|(name, value)| vec![name.to_string(), value])
+        .collect();
+    print_table(out, &["Field", "Value"], &rows)
+}
+
+fn print_matches(
+    out: &mut impl Write,
+    steam64_id: &str,
+    matches: &[MatchDetailsResponse],
+) -> io::Result<()> {
+    let rows: Vec<_> =
+        matches
+            .iter()
+            .map(|m| {
+                let stats = m.player_stats(steam64_id);
+                let [a, b] = &m.team_scores;
+                let score = match stats {
+                    Some(s) if s.initial_team_number == b.team_number => {
+                        format!("{}-{}", b.score, a.score)
+                    }
+                    _ => format!("{}-{}", a.score, b.score),
+                };
+                vec![
+                    m.finished_at.format("%Y-%m-%d %H:%M").to_string(),
+                    m.map_name.clone(),
+                    m.data_source.clone(),
+                    score,
+                    optional(stats.map(|s| {
+                        format!("{}/{}/{}", s.total_kills, s.total_assists, s.total_deaths)
+                    })),
+                    optional(
+                        stats
+                            .and_then(|s| s.leetify_rating)
+                            .map(|r| format!("{:+.2}", r)),
+                    ),
+                    m.id.clone(),
+                ]
+            })
+            .collect();
+
+    print_table(
+        out,
+        &[
+            "Finished", "Map", "Source", "Score", "K/A/D", "Rating", "Match ID",
+        ],
+        &rows,
+    )
+}
+
+fn print_match(
+    out: &mut impl Write,
+    details: &MatchDetailsResponse,
+    highlight: Option<String>,
+) -> io::Result<()> {
+    let style = if io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none() {
+        Style::Ansi
+    } else {
+        Style::Plain
+    };
+
+    let mut scoreboard = Scoreboard::new(details);
+    if let Some(steam64_id) = highlight {
+        scoreboard = scoreboard.highlight(steam64_id);
+    }
+    write!(out, "{}", scoreboard.render(style))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(exit_code(&Error::InvalidApiKey), 3);
+        assert_eq!(
+            exit_code(&Error::RateLimited(Some(Duration::from_secs(1)))),
+            4
+        );
+        assert_eq!(exit_code(&Error::Api(404, String::new())), 5);
+        assert_eq!(exit_code(&Error::InvalidGameId(String::new())), 2);
+    }
+
+    #[test]
+    fn test_config() {
+        let config: Config = toml::from_str(r#"api_key = "secret""#).unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("secret"));
+        assert_eq!(config.base_url, None);
+
+        let missing = Path::new("/nonexistent/leetify.toml");
+        assert!(Config::load(Some(missing)).is_err());
+    }
+
+    #[test]
+    fn test_table_alignment() {
+        let mut out = Vec::new();
+        let rows = vec![vec!["de_nuke".to_string(), "13-7".to_string()]];
+        print_table(&mut out, &["Map", "Score"], &rows).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Map      Score\nde_nuke  13-7\n"
+        );
+    }
+}
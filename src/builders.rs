@@ -0,0 +1,921 @@
+//! Builders with realistic defaults for the response types
+//!
+//! Available with the `builders` feature, which `test-utils`, `proptest`
+//! and `arbitrary` enable too.
+//!
+//! Every struct in [`types`](crate::types) has a `builder()` that starts from
+//! a plausible value, so tests only need to set the fields they care about:
+//!
+//! ```
+//! use leetify::{MatchDetailsResponse, PlayerStats};
+//!
+//! let stats = PlayerStats::builder()
+//!     .name("carry")
+//!     .performance(13, 9, 30, 12)
+//!     .build();
+//! assert_eq!(stats.rounds_count, 22);
+//! assert_eq!(stats.kd_ratio, 2.5);
+//!
+//! // Ten players whose stats lines agree with the score
+//! let details = MatchDetailsResponse::builder()
+//!     .map_name("de_nuke")
+//!     .score(7, 13)
+//!     .build();
+//! assert_eq!(details.stats[0].rounds_lost, 13);
+//! ```
+//!
+//! Setters assign fields as given. [`PlayerStatsBuilder::performance`],
+//! [`MatchDetailsResponseBuilder::score`] and
+//! [`ProfileResponseBuilder::history`] additionally recompute the fields
+//! that depend on them, keeping counts consistent (e.g. `rounds_won +
+//! rounds_lost == rounds_count`, `shots_hit_foe <= shots_fired`).
+
+use crate::types::{
+    CompetitiveRank, MatchDetailsResponse, PlatformBanInfo, PlayerStats, ProfileResponse, Ranks,
+    Rating, RecentMatch, RecentTeammate, Stats, TeamScore,
+};
+use chrono::{DateTime, Utc};
+
+/// Steam64 ID of the default player
+pub const STEAM64_ID: &str = "76561198283431555";
+
+/// Leetify ID of the default player
+pub const LEETIFY_ID: &str = "5ea07280-2399-4c7e-88ab-f2f7db0c449f";
+
+/// Name of the default player
+pub const NAME: &str = "player";
+
+/// ID of the default match
+const MATCH_ID: &str = "00000000-0000-4000-8000-000000000001";
+
+/// Finish time of the default match (2025-01-01 12:00 UTC)
+fn finished_at() -> DateTime<Utc> {
+    DateTime::from_timestamp(1_735_732_800, 0).unwrap_or_default()
+}
+
+/// Lower bounds of the FACEIT Elo of levels 2 to 10
+const FACEIT_LEVELS: [u32; 9] = [501, 751, 901, 1051, 1201, 1351, 1531, 1751, 2001];
+
+/// FACEIT level of an Elo rating
+pub(crate) fn faceit_level(elo: u32) -> u32 {
+    1 + FACEIT_LEVELS.iter().filter(|&&min| elo >= min).count() as u32
+}
+
+/// Steam64 ID of the `n`th generated player
+pub(crate) fn steam64_id(n: u64) -> String {
+    (76_561_197_960_265_728 + n).to_string()
+}
+
+macro_rules! builder {
+    (
+        $(#[$meta:meta])*
+        $builder:ident => $target:ident = $default:expr;
+        { $($field:ident: $ty:ty),* $(,)? }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $builder {
+            inner: $target,
+        }
+
+        impl $builder {
+            $(
+                #[doc = concat!("Set `", stringify!($field), "`")]
+                pub fn $field(mut self, value: impl Into<$ty>) -> Self {
+                    self.inner.$field = value.into();
+                    self
+                }
+            )*
+
+            pub fn build(self) -> $target {
+                self.inner
+            }
+        }
+
+        impl Default for $builder {
+            fn default() -> Self {
+                Self { inner: $default }
+            }
+        }
+
+        impl $target {
+            #[doc = concat!("Builder for a `", stringify!($target), "` with realistic defaults")]
+            pub fn builder() -> $builder {
+                $builder::default()
+            }
+        }
+
+        impl From<$builder> for $target {
+            fn from(builder: $builder) -> Self {
+                builder.build()
+            }
+        }
+    };
+}
+
+builder! {
+    /// Builder for [`ProfileResponse`]; defaults to a public profile with
+    /// one recent match (the default match)
+    ProfileResponseBuilder => ProfileResponse = default_profile();
+    {
+        privacy_mode: String,
+        winrate: f64,
+        total_matches: u32,
+        first_match_date: Option<DateTime<Utc>>,
+        name: String,
+        bans: Vec<PlatformBanInfo>,
+        steam64_id: String,
+        id: Option<String>,
+        ranks: Ranks,
+        rating: Rating,
+        stats: Stats,
+        recent_matches: Vec<RecentMatch>,
+        recent_teammates: Vec<RecentTeammate>,
+    }
+}
+
+builder! {
+    /// Builder for [`Ranks`]; defaults to Premier 15,250 and FACEIT level 7
+    RanksBuilder => Ranks = Ranks {
+        leetify: Some(1.35),
+        premier: Some(15_250),
+        faceit: Some(faceit_level(1_480)),
+        faceit_elo: Some(1_480),
+        wingman: Some(12),
+        renown: Some(9_800),
+        competitive: vec![CompetitiveRank::builder().build()],
+    };
+    {
+        leetify: Option<f64>,
+        premier: Option<u32>,
+        faceit: Option<u32>,
+        faceit_elo: Option<u32>,
+        wingman: Option<u32>,
+        renown: Option<u32>,
+        competitive: Vec<CompetitiveRank>,
+    }
+}
+
+builder! {
+    /// Builder for [`CompetitiveRank`]; defaults to Gold Nova Master on Mirage
+    CompetitiveRankBuilder => CompetitiveRank = CompetitiveRank {
+        map_name: "de_mirage".to_string(),
+        rank: 10,
+    };
+    {
+        map_name: String,
+        rank: u32,
+    }
+}
+
+builder! {
+    /// Builder for [`Rating`]; defaults to a slightly above average player
+    RatingBuilder => Rating = Rating {
+        aim: 61.2,
+        positioning: 54.8,
+        utility: 47.3,
+        clutch: 0.12,
+        opening: 0.03,
+        ct_leetify: 0.015,
+        t_leetify: 0.008,
+    };
+    {
+        aim: f64,
+        positioning: f64,
+        utility: f64,
+        clutch: f64,
+        opening: f64,
+        ct_leetify: f64,
+        t_leetify: f64,
+    }
+}
+
+builder! {
+    /// Builder for [`Stats`]; percentages are in the 0-100 range as returned
+    /// by the API
+    StatsBuilder => Stats = Stats {
+        accuracy_enemy_spotted: 33.0,
+        accuracy_head: 21.0,
+        counter_strafing_good_shots_ratio: 80.0,
+        ct_opening_aggression_success_rate: 45.0,
+        ct_opening_duel_success_percentage: 52.0,
+        flashbang_hit_foe_avg_duration: 2.4,
+        flashbang_hit_foe_per_flashbang: 0.8,
+        flashbang_hit_friend_per_flashbang: 0.2,
+        flashbang_leading_to_kill: 0.2,
+        flashbang_thrown: 9.0,
+        he_foes_damage_avg: 9.5,
+        he_friends_damage_avg: 0.8,
+        preaim: 9.5,
+        reaction_time_ms: 610.0,
+        spray_accuracy: 34.0,
+        t_opening_aggression_success_rate: 41.0,
+        t_opening_duel_success_percentage: 49.0,
+        traded_deaths_success_percentage: 50.0,
+        trade_kill_opportunities_per_round: 0.36,
+        trade_kills_success_percentage: 60.0,
+        utility_on_death_avg: 120.0,
+    };
+    {
+        accuracy_enemy_spotted: f64,
+        accuracy_head: f64,
+        counter_strafing_good_shots_ratio: f64,
+        ct_opening_aggression_success_rate: f64,
+        ct_opening_duel_success_percentage: f64,
+        flashbang_hit_foe_avg_duration: f64,
+        flashbang_hit_foe_per_flashbang: f64,
+        flashbang_hit_friend_per_flashbang: f64,
+        flashbang_leading_to_kill: f64,
+        flashbang_thrown: f64,
+        he_foes_damage_avg: f64,
+        he_friends_damage_avg: f64,
+        preaim: f64,
+        reaction_time_ms: f64,
+        spray_accuracy: f64,
+        t_opening_aggression_success_rate: f64,
+        t_opening_duel_success_percentage: f64,
+        traded_deaths_success_percentage: f64,
+        trade_kill_opportunities_per_round: f64,
+        trade_kills_success_percentage: f64,
+        utility_on_death_avg: f64,
+    }
+}
+
+builder! {
+    /// Builder for [`RecentMatch`]; defaults to the default player's view of
+    /// the default match
+    RecentMatchBuilder => RecentMatch = recent_match(
+        &MatchDetailsResponse::builder().build(),
+        STEAM64_ID,
+        Some(15_250),
+    )
+    .unwrap_or_default();
+    {
+        id: String,
+        finished_at: DateTime<Utc>,
+        data_source: String,
+        outcome: String,
+        rank: u32,
+        rank_type: Option<u32>,
+        map_name: String,
+        leetify_rating: f64,
+        score: [u32; 2],
+        preaim: f64,
+        reaction_time_ms: u32,
+        accuracy_enemy_spotted: f64,
+        accuracy_head: f64,
+        spray_accuracy: f64,
+    }
+}
+
+builder! {
+    /// Builder for [`RecentTeammate`]
+    RecentTeammateBuilder => RecentTeammate = RecentTeammate {
+        steam64_id: steam64_id(1),
+        recent_matches_count: 1,
+    };
+    {
+        steam64_id: String,
+        recent_matches_count: u32,
+    }
+}
+
+builder! {
+    /// Builder for [`PlatformBanInfo`]; defaults to a FACEIT ban
+    PlatformBanInfoBuilder => PlatformBanInfo = PlatformBanInfo {
+        platform: "faceit".to_string(),
+        platform_nickname: NAME.to_string(),
+        banned_since: finished_at(),
+    };
+    {
+        platform: String,
+        platform_nickname: String,
+        banned_since: DateTime<Utc>,
+    }
+}
+
+builder! {
+    /// Builder for [`MatchDetailsResponse`]; defaults to a ten player
+    /// Matchmaking game on Mirage that team 2 won 13 : 9, with the default
+    /// player on team 2
+    MatchDetailsResponseBuilder => MatchDetailsResponse = default_match();
+    {
+        id: String,
+        finished_at: DateTime<Utc>,
+        data_source: String,
+        data_source_match_id: String,
+        map_name: String,
+        has_banned_player: bool,
+        team_scores: [TeamScore; 2],
+        stats: Vec<PlayerStats>,
+    }
+}
+
+builder! {
+    /// Builder for [`TeamScore`]
+    TeamScoreBuilder => TeamScore = TeamScore {
+        team_number: 2,
+        score: 13,
+    };
+    {
+        team_number: u32,
+        score: u32,
+    }
+}
+
+builder! {
+    /// Builder for [`PlayerStats`]; defaults to 18 kills and 14 deaths in a
+    /// 13 : 9 win
+    PlayerStatsBuilder => PlayerStats = default_player_stats();
+    {
+        steam64_id: String,
+        name: String,
+        mvps: u32,
+        preaim: f64,
+        reaction_time: f64,
+        accuracy: f64,
+        accuracy_enemy_spotted: f64,
+        accuracy_head: f64,
+        shots_fired_enemy_spotted: u32,
+        shots_fired: u32,
+        shots_hit_enemy_spotted: u32,
+        shots_hit_friend: u32,
+        shots_hit_friend_head: u32,
+        shots_hit_foe: u32,
+        shots_hit_foe_head: u32,
+        utility_on_death_avg: f64,
+        he_foes_damage_avg: f64,
+        he_friends_damage_avg: f64,
+        he_thrown: u32,
+        molotov_thrown: u32,
+        smoke_thrown: u32,
+        counter_strafing_shots_all: u32,
+        counter_strafing_shots_bad: u32,
+        counter_strafing_shots_good: u32,
+        counter_strafing_shots_good_ratio: f64,
+        flashbang_hit_foe: u32,
+        flashbang_leading_to_kill: u32,
+        flashbang_hit_foe_avg_duration: f64,
+        flashbang_hit_friend: u32,
+        flashbang_thrown: u32,
+        flash_assist: u32,
+        score: u32,
+        initial_team_number: u32,
+        spray_accuracy: f64,
+        total_kills: u32,
+        total_deaths: u32,
+        kd_ratio: f64,
+        rounds_survived: u32,
+        rounds_survived_percentage: f64,
+        dpr: f64,
+        total_assists: u32,
+        total_damage: u32,
+        leetify_rating: Option<f64>,
+        ct_leetify_rating: Option<f64>,
+        t_leetify_rating: Option<f64>,
+        multi1k: u32,
+        multi2k: u32,
+        multi3k: u32,
+        multi4k: u32,
+        multi5k: u32,
+        rounds_count: u32,
+        rounds_won: u32,
+        rounds_lost: u32,
+        total_hs_kills: u32,
+        trade_kill_opportunities: u32,
+        trade_kill_attempts: u32,
+        trade_kills_succeed: u32,
+        trade_kill_attempts_percentage: f64,
+        trade_kills_success_percentage: f64,
+        trade_kill_opportunities_per_round: f64,
+        traded_death_opportunities: u32,
+        traded_death_attempts: u32,
+        traded_deaths_succeed: u32,
+        traded_death_attempts_percentage: f64,
+        traded_deaths_success_percentage: f64,
+        traded_deaths_opportunities_per_round: f64,
+    }
+}
+
+impl PlayerStatsBuilder {
+    /// Set the rounds won and lost by the player's team and their kills and
+    /// deaths, and derive every other count and ratio from them
+    ///
+    /// Kills are capped at five per round and deaths at one per round.
+    pub fn performance(mut self, won: u32, lost: u32, kills: u32, deaths: u32) -> Self {
+        Line::typical(won, lost, kills, deaths).apply(&mut self.inner);
+        self
+    }
+}
+
+impl MatchDetailsResponseBuilder {
+    /// Set the scores of teams 2 and 3 and refit every player's rounds to
+    /// them, keeping their kills and deaths
+    pub fn score(mut self, team2: u32, team3: u32) -> Self {
+        self.inner.team_scores = [
+            TeamScore {
+                team_number: 2,
+                score: team2,
+            },
+            TeamScore {
+                team_number: 3,
+                score: team3,
+            },
+        ];
+        for stats in &mut self.inner.stats {
+            let (won, lost) = if stats.initial_team_number == 3 {
+                (team3, team2)
+            } else {
+                (team2, team3)
+            };
+            let mut line = Line::of(stats);
+            line.won = won;
+            line.lost = lost;
+            line.apply(stats);
+        }
+        self
+    }
+
+    /// Add a stats line
+    pub fn player(mut self, stats: impl Into<PlayerStats>) -> Self {
+        self.inner.stats.push(stats.into());
+        self
+    }
+}
+
+impl ProfileResponseBuilder {
+    /// Replace the recent matches and teammates with the ones of `matches`
+    /// (newest first), and raise `total_matches` and lower
+    /// `first_match_date` to cover them
+    ///
+    /// Matches without a stats line for the profile's Steam64 ID are
+    /// skipped.
+    pub fn history(mut self, matches: &[MatchDetailsResponse]) -> Self {
+        let profile = &mut self.inner;
+        profile.recent_matches = matches
+            .iter()
+            .filter_map(|m| recent_match(m, &profile.steam64_id, profile.ranks.premier))
+            .collect();
+
+        let mut teammates: Vec<RecentTeammate> = Vec::new();
+        for details in matches {
+            let Some(own) = details.player_stats(&profile.steam64_id) else {
+                continue;
+            };
+            let team = details.stats.iter().filter(|s| {
+                s.initial_team_number == own.initial_team_number && s.steam64_id != own.steam64_id
+            });
+            for stats in team {
+                match teammates
+                    .iter_mut()
+                    .find(|t| t.steam64_id == stats.steam64_id)
+                {
+                    Some(teammate) => teammate.recent_matches_count += 1,
+                    None => teammates.push(RecentTeammate {
+                        steam64_id: stats.steam64_id.clone(),
+                        recent_matches_count: 1,
+                    }),
+                }
+            }
+        }
+        profile.recent_teammates = teammates;
+
+        let count = profile.recent_matches.len() as u32;
+        profile.total_matches = profile.total_matches.max(count);
+        if let Some(oldest) = profile.recent_matches.iter().map(|m| m.finished_at).min() {
+            profile.first_match_date =
+                Some(profile.first_match_date.map_or(oldest, |d| d.min(oldest)));
+        }
+        self
+    }
+}
+
+/// The recent match entry of a profile for one of its matches
+pub(crate) fn recent_match(
+    details: &MatchDetailsResponse,
+    steam64_id: &str,
+    premier: Option<u32>,
+) -> Option<RecentMatch> {
+    let stats = details.player_stats(steam64_id)?;
+    let [a, b] = &details.team_scores;
+    let (team, enemy) = if stats.initial_team_number == b.team_number {
+        (b.score, a.score)
+    } else {
+        (a.score, b.score)
+    };
+
+    Some(RecentMatch {
+        id: details.id.clone(),
+        finished_at: details.finished_at,
+        data_source: details.data_source.clone(),
+        outcome: outcome(team, enemy).to_string(),
+        rank: premier.unwrap_or_default(),
+        rank_type: Some(11),
+        map_name: details.map_name.clone(),
+        leetify_rating: stats.leetify_rating.unwrap_or_default(),
+        score: [team, enemy],
+        preaim: stats.preaim,
+        reaction_time_ms: (stats.reaction_time * 1000.0).round() as u32,
+        accuracy_enemy_spotted: stats.accuracy_enemy_spotted,
+        accuracy_head: stats.accuracy_head,
+        spray_accuracy: stats.spray_accuracy,
+    })
+}
+
+/// The `outcome` of a recent match the player's team finished `team` :
+/// `enemy`
+pub(crate) fn outcome(team: u32, enemy: u32) -> &'static str {
+    match team.cmp(&enemy) {
+        std::cmp::Ordering::Greater => "win",
+        std::cmp::Ordering::Less => "loss",
+        std::cmp::Ordering::Equal => "tie",
+    }
+}
+
+/// Stats line of the `n`th player of a generated lobby
+pub(crate) fn lobby_player(n: u64, team_number: u32, line: Line) -> PlayerStats {
+    let mut stats = PlayerStats::builder()
+        .steam64_id(steam64_id(n))
+        .name(format!("player{}", n))
+        .initial_team_number(team_number)
+        .build();
+    line.apply(&mut stats);
+    stats
+}
+
+fn default_player_stats() -> PlayerStats {
+    let mut stats = PlayerStats {
+        steam64_id: STEAM64_ID.to_string(),
+        name: NAME.to_string(),
+        preaim: 9.5,
+        reaction_time: 0.61,
+        shots_hit_friend: 1,
+        shots_hit_friend_head: 0,
+        utility_on_death_avg: 120.0,
+        he_foes_damage_avg: 9.5,
+        he_friends_damage_avg: 0.8,
+        he_thrown: 6,
+        molotov_thrown: 5,
+        smoke_thrown: 7,
+        flashbang_hit_foe: 8,
+        flashbang_leading_to_kill: 2,
+        flashbang_hit_foe_avg_duration: 2.4,
+        flashbang_hit_friend: 2,
+        flashbang_thrown: 9,
+        flash_assist: 1,
+        initial_team_number: 2,
+        spray_accuracy: 0.34,
+        ..Default::default()
+    };
+    Line::typical(13, 9, 18, 14).apply(&mut stats);
+    stats
+}
+
+fn default_match() -> MatchDetailsResponse {
+    let (won, lost) = (13, 9);
+    let mut stats = vec![default_player_stats()];
+    for n in 1..10 {
+        let (team, team_won, team_lost) = if n < 5 {
+            (2, won, lost)
+        } else {
+            (3, lost, won)
+        };
+        let kills = 8 + n as u32 * 2;
+        stats.push(lobby_player(
+            n,
+            team,
+            Line::typical(team_won, team_lost, kills, 12 + n as u32 % 5),
+        ));
+    }
+
+    MatchDetailsResponse {
+        id: MATCH_ID.to_string(),
+        finished_at: finished_at(),
+        data_source: "matchmaking".to_string(),
+        data_source_match_id: "3761237621376213762".to_string(),
+        map_name: "de_mirage".to_string(),
+        has_banned_player: false,
+        team_scores: [
+            TeamScore {
+                team_number: 2,
+                score: won,
+            },
+            TeamScore {
+                team_number: 3,
+                score: lost,
+            },
+        ],
+        stats,
+    }
+}
+
+fn default_profile() -> ProfileResponse {
+    let profile = ProfileResponse {
+        privacy_mode: "public".to_string(),
+        winrate: 0.52,
+        total_matches: 250,
+        first_match_date: DateTime::from_timestamp(1_577_880_000, 0),
+        name: NAME.to_string(),
+        bans: Vec::new(),
+        steam64_id: STEAM64_ID.to_string(),
+        id: Some(LEETIFY_ID.to_string()),
+        ranks: Ranks::builder().build(),
+        rating: Rating::builder().build(),
+        stats: Stats::builder().build(),
+        recent_matches: Vec::new(),
+        recent_teammates: Vec::new(),
+    };
+    ProfileResponseBuilder { inner: profile }
+        .history(&[default_match()])
+        .build()
+}
+
+/// Primary counts of a stats line from which all other counts and ratios
+/// are derived
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Line {
+    pub won: u32,
+    pub lost: u32,
+    pub kills: u32,
+    pub deaths: u32,
+    pub assists: u32,
+    pub hs_kills: u32,
+    pub shots_fired: u32,
+    pub shots_hit: u32,
+    pub damage: u32,
+    pub rating: f64,
+}
+
+impl Line {
+    /// A line with typical ratios for the given result, kills and deaths
+    pub fn typical(won: u32, lost: u32, kills: u32, deaths: u32) -> Self {
+        Self {
+            won,
+            lost,
+            kills,
+            deaths,
+            assists: 3 + kills / 5,
+            hs_kills: kills * 45 / 100,
+            shots_fired: kills * 9 + 40,
+            shots_hit: kills * 3 + 10,
+            damage: kills * 95 + 150,
+            rating: (kills as f64 - deaths as f64) / 300.0,
+        }
+    }
+
+    /// The primary counts of an existing stats line
+    pub fn of(stats: &PlayerStats) -> Self {
+        Self {
+            won: stats.rounds_won,
+            lost: stats.rounds_lost,
+            kills: stats.total_kills,
+            deaths: stats.total_deaths,
+            assists: stats.total_assists,
+            hs_kills: stats.total_hs_kills,
+            shots_fired: stats.shots_fired,
+            shots_hit: stats.shots_hit_foe,
+            damage: stats.total_damage,
+            rating: stats.leetify_rating.unwrap_or_default(),
+        }
+    }
+
+    /// Overwrite every count and ratio of `stats` that depends on this line
+    pub fn apply(self, stats: &mut PlayerStats) {
+        let rounds = self.won + self.lost;
+        let per_round = |value: u32| value as f64 / rounds.max(1) as f64;
+        let share = |part: u32, whole: u32| {
+            if whole == 0 {
+                0.0
+            } else {
+                part as f64 / whole as f64
+            }
+        };
+
+        let kills = self.kills.min(rounds * 5);
+        let deaths = self.deaths.min(rounds);
+        let hs_kills = self.hs_kills.min(kills);
+        let shots_fired = self.shots_fired;
+        let shots_hit = self.shots_hit.min(shots_fired);
+        let spotted = (shots_fired * 6 / 10).max(shots_hit);
+        let hit_spotted = shots_hit * 8 / 10;
+        let hit_head = shots_hit / 4;
+        let multi = multi_kills(kills, rounds);
+        let mvps = (kills / 6).min(self.won);
+
+        stats.rounds_count = rounds;
+        stats.rounds_won = self.won;
+        stats.rounds_lost = self.lost;
+        stats.total_kills = kills;
+        stats.total_deaths = deaths;
+        stats.total_assists = self.assists;
+        stats.total_hs_kills = hs_kills;
+        stats.total_damage = self.damage;
+        stats.kd_ratio = kills as f64 / deaths.max(1) as f64;
+        stats.dpr = per_round(self.damage);
+        stats.rounds_survived = rounds - deaths;
+        stats.rounds_survived_percentage = share(rounds - deaths, rounds) * 100.0;
+        [
+            stats.multi1k,
+            stats.multi2k,
+            stats.multi3k,
+            stats.multi4k,
+            stats.multi5k,
+        ] = multi;
+        stats.mvps = mvps;
+        stats.score = kills * 2 + self.assists + mvps * 2;
+
+        stats.shots_fired = shots_fired;
+        stats.shots_hit_foe = shots_hit;
+        stats.shots_hit_foe_head = hit_head;
+        stats.shots_fired_enemy_spotted = spotted;
+        stats.shots_hit_enemy_spotted = hit_spotted;
+        stats.accuracy = share(shots_hit, shots_fired);
+        stats.accuracy_enemy_spotted = share(hit_spotted, spotted);
+        stats.accuracy_head = share(hit_head, shots_hit);
+        stats.counter_strafing_shots_all = shots_fired / 3;
+        stats.counter_strafing_shots_good = shots_fired / 3 * 8 / 10;
+        stats.counter_strafing_shots_bad =
+            stats.counter_strafing_shots_all - stats.counter_strafing_shots_good;
+        stats.counter_strafing_shots_good_ratio = share(
+            stats.counter_strafing_shots_good,
+            stats.counter_strafing_shots_all,
+        );
+
+        stats.trade_kill_opportunities = rounds * 3 / 10;
+        stats.trade_kill_attempts = stats.trade_kill_opportunities * 6 / 10;
+        stats.trade_kills_succeed = stats.trade_kill_attempts * 6 / 10;
+        stats.trade_kill_attempts_percentage =
+            share(stats.trade_kill_attempts, stats.trade_kill_opportunities) * 100.0;
+        stats.trade_kills_success_percentage =
+            share(stats.trade_kills_succeed, stats.trade_kill_attempts) * 100.0;
+        stats.trade_kill_opportunities_per_round = per_round(stats.trade_kill_opportunities);
+        stats.traded_death_opportunities = deaths * 6 / 10;
+        stats.traded_death_attempts = stats.traded_death_opportunities * 7 / 10;
+        stats.traded_deaths_succeed = stats.traded_death_attempts / 2;
+        stats.traded_death_attempts_percentage = share(
+            stats.traded_death_attempts,
+            stats.traded_death_opportunities,
+        ) * 100.0;
+        stats.traded_deaths_success_percentage =
+            share(stats.traded_deaths_succeed, stats.traded_death_attempts) * 100.0;
+        stats.traded_deaths_opportunities_per_round = per_round(stats.traded_death_opportunities);
+
+        stats.leetify_rating = Some(self.rating);
+        stats.ct_leetify_rating = Some(self.rating * 1.1);
+        stats.t_leetify_rating = Some(self.rating * 0.9);
+    }
+}
+
+/// Number of rounds with exactly 1 to 5 kills, for `kills` kills spread over
+/// at most `rounds` rounds
+///
+/// The counts satisfy `sum(k * multi[k - 1]) == kills` and
+/// `sum(multi) <= rounds` whenever `kills <= 5 * rounds`.
+fn multi_kills(kills: u32, rounds: u32) -> [u32; 5] {
+    let mut multi = [0; 5];
+    if kills == 0 || rounds == 0 {
+        return multi;
+    }
+
+    // One 3K per 15 kills, if the remaining kills still fit
+    let mut triples = kills / 15;
+    if kills - triples * 3 > 4 * (rounds - triples.min(rounds)) {
+        triples = 0;
+    }
+    multi[2] = triples;
+
+    // Spread the rest over two thirds as many rounds as there are kills
+    let rest = kills - triples * 3;
+    if rest > 0 {
+        let active = (rest * 2).div_ceil(3).clamp(1, rounds - triples);
+        let (each, extra) = (rest / active, rest % active);
+        multi[each as usize - 1] += active - extra;
+        if extra > 0 {
+            multi[each as usize] += extra;
+        }
+    }
+    multi
+}
+
+/// Invariants that built and generated values satisfy, checked by the tests
+/// of the builders and of the `proptest` and `arbitrary` generators
+#[cfg(test)]
+pub(crate) mod checks {
+    use super::*;
+
+    pub(crate) fn player_stats(stats: &PlayerStats) {
+        assert_eq!(stats.rounds_won + stats.rounds_lost, stats.rounds_count);
+        assert!(stats.total_deaths <= stats.rounds_count);
+        assert!(stats.total_hs_kills <= stats.total_kills);
+        assert!(stats.shots_hit_foe <= stats.shots_fired);
+        assert!(stats.shots_hit_foe_head <= stats.shots_hit_foe);
+        assert!(stats.shots_hit_enemy_spotted <= stats.shots_fired_enemy_spotted);
+        assert!(stats.trade_kills_succeed <= stats.trade_kill_attempts);
+        assert!(stats.trade_kill_attempts <= stats.trade_kill_opportunities);
+        assert!(stats.mvps <= stats.rounds_won);
+        let multi = [
+            stats.multi1k,
+            stats.multi2k,
+            stats.multi3k,
+            stats.multi4k,
+            stats.multi5k,
+        ];
+        let kills: u32 = multi.iter().zip(1..).map(|(m, k)| m * k).sum();
+        assert_eq!(kills, stats.total_kills);
+        assert!(multi.iter().sum::<u32>() <= stats.rounds_count);
+    }
+
+    pub(crate) fn match_details(details: &MatchDetailsResponse) {
+        let mut ids: Vec<_> = details.stats.iter().map(|s| &s.steam64_id).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), details.stats.len());
+
+        for stats in &details.stats {
+            player_stats(stats);
+            let [own, enemy] = if stats.initial_team_number == 2 {
+                [&details.team_scores[0], &details.team_scores[1]]
+            } else {
+                [&details.team_scores[1], &details.team_scores[0]]
+            };
+            assert_eq!(own.team_number, stats.initial_team_number);
+            assert_eq!(
+                (stats.rounds_won, stats.rounds_lost),
+                (own.score, enemy.score)
+            );
+        }
+    }
+
+    pub(crate) fn profile(profile: &ProfileResponse) {
+        assert!(profile.total_matches as usize >= profile.recent_matches.len());
+        assert!((0.0..=1.0).contains(&profile.winrate));
+        if let (Some(level), Some(elo)) = (profile.ranks.faceit, profile.ranks.faceit_elo) {
+            assert!(level == 1 || elo >= FACEIT_LEVELS[level as usize - 2]);
+            assert!(level == 10 || elo < FACEIT_LEVELS[level as usize - 1]);
+        }
+        for (newer, older) in profile
+            .recent_matches
+            .iter()
+            .zip(profile.recent_matches.iter().skip(1))
+        {
+            assert!(newer.finished_at >= older.finished_at);
+            assert_ne!(newer.id, older.id);
+        }
+        for recent in &profile.recent_matches {
+            assert!(
+                profile
+                    .first_match_date
+                    .is_some_and(|first| first <= recent.finished_at)
+            );
+            let [own, enemy] = recent.score;
+            assert_eq!(recent.outcome, outcome(own, enemy));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_are_consistent() {
+        let details = MatchDetailsResponse::builder().build();
+        assert_eq!(details.stats.len(), 10);
+        checks::match_details(&details);
+
+        let profile = ProfileResponse::builder().build();
+        checks::profile(&profile);
+        assert_eq!(profile.recent_matches[0].id, details.id);
+        assert_eq!(profile.recent_matches[0].outcome, "win");
+        assert_eq!(profile.recent_teammates.len(), 4);
+    }
+
+    #[test]
+    fn test_performance_and_score() {
+        let stats = PlayerStats::builder().performance(13, 9, 30, 12).build();
+        checks::player_stats(&stats);
+        assert_eq!(stats.kd_ratio, 2.5);
+
+        let stats = PlayerStats::builder().performance(1, 1, 50, 5).build();
+        checks::player_stats(&stats);
+        assert_eq!((stats.total_kills, stats.total_deaths), (10, 2));
+
+        let details = MatchDetailsResponse::builder().score(7, 13).build();
+        let loser = details.player_stats(STEAM64_ID).unwrap();
+        assert_eq!((loser.rounds_won, loser.rounds_lost), (7, 13));
+        assert_eq!(loser.total_kills, 18);
+        checks::player_stats(loser);
+    }
+
+    #[test]
+    fn test_multi_kills() {
+        for rounds in 0..30 {
+            for kills in 0..=rounds * 5 {
+                let multi = multi_kills(kills, rounds);
+                let total: u32 = multi.iter().zip(1..).map(|(m, k)| m * k).sum();
+                assert_eq!(total, kills, "{} kills in {} rounds", kills, rounds);
+                assert!(multi.iter().sum::<u32>() <= rounds);
+            }
+        }
+    }
+}
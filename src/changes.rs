@@ -0,0 +1,318 @@
+//! Differences between two snapshots of a player's profile
+
+use crate::types::{PlatformBanInfo, ProfileResponse, Rating};
+use serde::{Deserialize, Serialize};
+
+/// Premier rating tier, named after the color of the rating badge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum PremierTier {
+    /// Below 5,000
+    Gray,
+    /// 5,000 - 9,999
+    LightBlue,
+    /// 10,000 - 14,999
+    Blue,
+    /// 15,000 - 19,999
+    Purple,
+    /// 20,000 - 24,999
+    Pink,
+    /// 25,000 - 29,999
+    Red,
+    /// 30,000 and above
+    Gold,
+}
+
+impl PremierTier {
+    /// Get the tier of a Premier rating
+    pub fn from_rating(rating: u32) -> Self {
+        match rating {
+            0..5_000 => PremierTier::Gray,
+            5_000..10_000 => PremierTier::LightBlue,
+            10_000..15_000 => PremierTier::Blue,
+            15_000..20_000 => PremierTier::Purple,
+            20_000..25_000 => PremierTier::Pink,
+            25_000..30_000 => PremierTier::Red,
+            _ => PremierTier::Gold,
+        }
+    }
+}
+
+/// A component of a player's `Rating`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RatingComponent {
+    Aim,
+    Positioning,
+    Utility,
+    Clutch,
+    Opening,
+    CtLeetify,
+    TLeetify,
+}
+
+impl RatingComponent {
+    /// All components
+    pub const ALL: [RatingComponent; 7] = [
+        RatingComponent::Aim,
+        RatingComponent::Positioning,
+        RatingComponent::Utility,
+        RatingComponent::Clutch,
+        RatingComponent::Opening,
+        RatingComponent::CtLeetify,
+        RatingComponent::TLeetify,
+    ];
+
+    /// Get the value of this component
+    pub fn value(&self, rating: &Rating) -> f64 {
+        match self {
+            RatingComponent::Aim => rating.aim,
+            RatingComponent::Positioning => rating.positioning,
+            RatingComponent::Utility => rating.utility,
+            RatingComponent::Clutch => rating.clutch,
+            RatingComponent::Opening => rating.opening,
+            RatingComponent::CtLeetify => rating.ct_leetify,
+            RatingComponent::TLeetify => rating.t_leetify,
+        }
+    }
+
+    /// Whether this is one of the side-specific Leetify ratings, which use a
+    /// much smaller scale than the other components
+    pub fn is_leetify_rating(&self) -> bool {
+        matches!(self, RatingComponent::CtLeetify | RatingComponent::TLeetify)
+    }
+}
+
+/// Minimum movements that are reported as changes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChangeThresholds {
+    /// For aim, positioning, utility, clutch and opening (default 2.0)
+    pub rating: f64,
+    /// For the CT and T side Leetify ratings (default 0.01)
+    pub leetify_rating: f64,
+}
+
+impl Default for ChangeThresholds {
+    fn default() -> Self {
+        Self {
+            rating: 2.0,
+            leetify_rating: 0.01,
+        }
+    }
+}
+
+/// A notable change between two profile snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ProfileChange {
+    /// The Premier rating crossed into another tier
+    PremierTier {
+        from: Option<PremierTier>,
+        to: Option<PremierTier>,
+        rating: Option<u32>,
+    },
+    /// The FACEIT level changed
+    FaceitLevel { from: Option<u32>, to: Option<u32> },
+    /// A competitive (map-based) rank changed
+    CompetitiveRank {
+        map_name: String,
+        from: Option<u32>,
+        to: Option<u32>,
+    },
+    /// A ban appeared that was not in the previous snapshot
+    NewBan(PlatformBanInfo),
+    /// A rating component moved by at least the configured threshold
+    Rating {
+        component: RatingComponent,
+        from: f64,
+        to: f64,
+    },
+}
+
+/// All notable changes between two snapshots of the same profile
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::{Client, Id};
+/// # use leetify::changes::{ChangeThresholds, ProfileDiff};
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let client = Client::new();
+/// let id = Id::Steam64("76561198283431555".into());
+///
+/// let before = client.get_profile(id.clone()).await?;
+/// // ... later ...
+/// let after = client.get_profile(id).await?;
+///
+/// for change in ProfileDiff::between(&before, &after, &ChangeThresholds::default()).changes {
+///     println!("{:?}", change);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileDiff {
+    pub changes: Vec<ProfileChange>,
+}
+
+impl ProfileDiff {
+    /// Compute the changes from `old` to `new`
+    pub fn between(
+        old: &ProfileResponse,
+        new: &ProfileResponse,
+        thresholds: &ChangeThresholds,
+    ) -> Self {
+        let mut changes = Vec::new();
+
+        let old_tier = old.ranks.premier.map(PremierTier::from_rating);
+        let new_tier = new.ranks.premier.map(PremierTier::from_rating);
+        if old_tier != new_tier {
+            changes.push(ProfileChange::PremierTier {
+                from: old_tier,
+                to: new_tier,
+                rating: new.ranks.premier,
+            });
+        }
+
+        if old.ranks.faceit != new.ranks.faceit {
+            changes.push(ProfileChange::FaceitLevel {
+                from: old.ranks.faceit,
+                to: new.ranks.faceit,
+            });
+        }
+
+        let rank = |profile: &ProfileResponse, map_name: &str| {
+            profile
+                .ranks
+                .competitive
+                .iter()
+                .find(|r| r.map_name == map_name)
+                .map(|r| r.rank)
+        };
+        let mut map_names: Vec<&str> = old
+            .ranks
+            .competitive
+            .iter()
+            .chain(&new.ranks.competitive)
+            .map(|r| r.map_name.as_str())
+            .collect();
+        map_names.sort_unstable();
+        map_names.dedup();
+        for map_name in map_names {
+            let (from, to) = (rank(old, map_name), rank(new, map_name));
+            if from != to {
+                changes.push(ProfileChange::CompetitiveRank {
+                    map_name: map_name.to_string(),
+                    from,
+                    to,
+                });
+            }
+        }
+
+        for ban in &new.bans {
+            let known = old
+                .bans
+                .iter()
+                .any(|b| b.platform == ban.platform && b.banned_since == ban.banned_since);
+            if !known {
+                changes.push(ProfileChange::NewBan(ban.clone()));
+            }
+        }
+
+        for component in RatingComponent::ALL {
+            let threshold = if component.is_leetify_rating() {
+                thresholds.leetify_rating
+            } else {
+                thresholds.rating
+            };
+            let (from, to) = (component.value(&old.rating), component.value(&new.rating));
+            if (to - from).abs() >= threshold {
+                changes.push(ProfileChange::Rating {
+                    component,
+                    from,
+                    to,
+                });
+            }
+        }
+
+        Self { changes }
+    }
+
+    /// Whether nothing notable changed
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CompetitiveRank, Ranks};
+
+    fn profile(premier: u32, faceit: u32, aim: f64, bans: usize) -> ProfileResponse {
+        ProfileResponse {
+            ranks: Ranks {
+                premier: Some(premier),
+                faceit: Some(faceit),
+                competitive: vec![CompetitiveRank {
+                    map_name: "de_nuke".to_string(),
+                    rank: faceit,
+                }],
+                ..Default::default()
+            },
+            rating: Rating {
+                aim,
+                ..Default::default()
+            },
+            bans: (0..bans)
+                .map(|i| PlatformBanInfo {
+                    platform: format!("platform-{i}"),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_premier_tiers() {
+        assert_eq!(PremierTier::from_rating(4_999), PremierTier::Gray);
+        assert_eq!(PremierTier::from_rating(15_000), PremierTier::Purple);
+        assert_eq!(PremierTier::from_rating(35_000), PremierTier::Gold);
+    }
+
+    #[test]
+    fn test_profile_diff() {
+        let thresholds = ChangeThresholds::default();
+        let old = profile(14_900, 5, 60.0, 0);
+
+        assert!(ProfileDiff::between(&old, &profile(14_950, 5, 61.0, 0), &thresholds).is_empty());
+
+        let diff = ProfileDiff::between(&old, &profile(15_100, 6, 63.0, 1), &thresholds);
+        assert_eq!(
+            diff.changes,
+            vec![
+                ProfileChange::PremierTier {
+                    from: Some(PremierTier::Blue),
+                    to: Some(PremierTier::Purple),
+                    rating: Some(15_100),
+                },
+                ProfileChange::FaceitLevel {
+                    from: Some(5),
+                    to: Some(6),
+                },
+                ProfileChange::CompetitiveRank {
+                    map_name: "de_nuke".to_string(),
+                    from: Some(5),
+                    to: Some(6),
+                },
+                ProfileChange::NewBan(PlatformBanInfo {
+                    platform: "platform-0".to_string(),
+                    ..Default::default()
+                }),
+                ProfileChange::Rating {
+                    component: RatingComponent::Aim,
+                    from: 60.0,
+                    to: 63.0,
+                },
+            ]
+        );
+    }
+}
@@ -1,9 +1,13 @@
 use crate::error::Error;
 use crate::types::*;
+#[cfg(feature = "vcr")]
+use crate::vcr::Vcr;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::Duration;
 
 const DEFAULT_BASE_URL: &str = "https://api-public.cs-prod.leetify.com";
-const API_KEY_HEADER: &str = "_leetify_key";
+pub(crate) const API_KEY_HEADER: &str = "_leetify_key";
 const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Builder for creating a customized `Client`
@@ -12,6 +16,8 @@ pub struct ClientBuilder {
     api_key: Option<String>,
     timeout: Option<Duration>,
     client_builder: reqwest::ClientBuilder,
+    #[cfg(feature = "vcr")]
+    vcr: Option<Vcr>,
 }
 
 impl ClientBuilder {
@@ -22,6 +28,8 @@ impl ClientBuilder {
             api_key: None,
             timeout: Some(DEFAULT_TIMEOUT),
             client_builder: reqwest::Client::builder(),
+            #[cfg(feature = "vcr")]
+            vcr: None,
         }
     }
 
@@ -86,6 +94,25 @@ impl ClientBuilder {
         self
     }
 
+    /// Record API interactions to, or replay them from, a cassette file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use leetify::Client;
+    /// use leetify::vcr::Vcr;
+    ///
+    /// let client = Client::builder()
+    ///     .vcr(Vcr::strict("tests/cassettes/profile.json")?)
+    ///     .build()?;
+    /// # Ok::<(), leetify::Error>(())
+    /// ```
+    #[cfg(feature = "vcr")]
+    pub fn vcr(mut self, vcr: Vcr) -> Self {
+        self.vcr = Some(vcr);
+        self
+    }
+
     /// Build the client
     ///
     /// # Examples
@@ -106,11 +133,16 @@ impl ClientBuilder {
             .map_err(Error::Http)?;
 
         Ok(Client {
-            client,
-            base_url: self
-                .base_url
-                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
-            api_key: self.api_key,
+            inner: Arc::new(ClientInner {
+                client,
+                base_url: self
+                    .base_url
+                    .unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+                api_key: self.api_key,
+                ids: Mutex::default(),
+                #[cfg(feature = "vcr")]
+                vcr: self.vcr,
+            }),
         })
     }
 }
@@ -122,10 +154,45 @@ impl Default for ClientBuilder {
 }
 
 /// Client for interacting with the Leetify Public CS API
+///
+/// The client is cheap to clone: clones share the same connection pool and
+/// configuration, so a clone can be handed to every task that needs one.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::{Client, Id};
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let client = Client::new();
+///
+/// let handle = tokio::spawn({
+///     let client = client.clone();
+///     async move { client.get_profile(Id::Steam64("76561198283431555".into())).await }
+/// });
+/// let profile = handle.await.unwrap()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
 pub struct Client {
+    inner: Arc<ClientInner>,
+}
+
+/// State shared between clones of a `Client`
+struct ClientInner {
     client: reqwest::Client,
     base_url: String,
     api_key: Option<String>,
+    ids: Mutex<IdMap>,
+    #[cfg(feature = "vcr")]
+    vcr: Option<Vcr>,
+}
+
+/// Known Steam64 ID <-> Leetify ID pairs
+#[derive(Default)]
+struct IdMap {
+    leetify_by_steam64: HashMap<Steam64Id, LeetifyId>,
+    steam64_by_leetify: HashMap<LeetifyId, Steam64Id>,
 }
 
 impl Client {
@@ -210,17 +277,25 @@ impl Client {
     pub async fn get_profile(&self, id: impl Into<Id>) -> Result<ProfileResponse, Error> {
         let id = id.into();
 
-        let url = format!("{}/v3/profile", self.base_url);
+        let url = format!("{}/v3/profile", self.inner.base_url);
         let query_params = self.build_profile_query_params(&id);
 
-        let mut request = self.client.get(&url);
+        let mut request = self.inner.client.get(&url);
         if !query_params.is_empty() {
             request = request.query(&query_params);
         }
         request = self.add_api_key_header(request);
 
-        let response = request.send().await?;
-        self.handle_response(response).await
+        let reply = self.execute(request).await?;
+        let profile: ProfileResponse = self.handle_response(reply)?;
+
+        if let Some(leetify_id) = &profile.id {
+            self.remember_ids(
+                profile.steam64_id.as_str().into(),
+                leetify_id.as_str().into(),
+            );
+        }
+        Ok(profile)
     }
 
     /// Get player match history
@@ -253,17 +328,17 @@ impl Client {
     ) -> Result<Vec<MatchDetailsResponse>, Error> {
         let id = id.into();
 
-        let url = format!("{}/v3/profile/matches", self.base_url);
+        let url = format!("{}/v3/profile/matches", self.inner.base_url);
         let query_params = self.build_profile_query_params(&id);
 
-        let mut request = self.client.get(&url);
+        let mut request = self.inner.client.get(&url);
         if !query_params.is_empty() {
             request = request.query(&query_params);
         }
         request = self.add_api_key_header(request);
 
-        let response = request.send().await?;
-        self.handle_response(response).await
+        let reply = self.execute(request).await?;
+        self.handle_response(reply)
     }
 
     /// Get match details by game ID
@@ -286,12 +361,12 @@ impl Client {
         &self,
         game_id: String,
     ) -> Result<MatchDetailsResponse, Error> {
-        let url = format!("{}/v2/matches/{}", self.base_url, game_id);
-        let request = self.client.get(&url);
+        let url = format!("{}/v2/matches/{}", self.inner.base_url, game_id);
+        let request = self.inner.client.get(&url);
         let request = self.add_api_key_header(request);
 
-        let response = request.send().await?;
-        self.handle_response(response).await
+        let reply = self.execute(request).await?;
+        self.handle_response(reply)
     }
 
     /// Get match details by data source and data source ID
@@ -327,15 +402,15 @@ impl Client {
         let data_source = data_source.into();
         let url = format!(
             "{}/v2/matches/{}/{}",
-            self.base_url,
+            self.inner.base_url,
             data_source.as_str(),
             data_source_id.as_ref()
         );
-        let request = self.client.get(&url);
+        let request = self.inner.client.get(&url);
         let request = self.add_api_key_header(request);
 
-        let response = request.send().await?;
-        self.handle_response(response).await
+        let reply = self.execute(request).await?;
+        self.handle_response(reply)
     }
 
     /// Validate the API key
@@ -343,6 +418,7 @@ impl Client {
     /// Returns:
     /// - `Ok(())` if the key is valid
     /// - `Err(Error::InvalidApiKey)` if the key is invalid or missing
+    /// - `Err(Error::RateLimited(_))` if too many requests were made
     /// - `Err(Error::ServerError)` if there was a server error
     ///
     /// # Examples
@@ -360,16 +436,18 @@ impl Client {
     /// # }
     /// ```
     pub async fn validate_api_key(&self) -> Result<(), Error> {
-        let url = format!("{}/api-key/validate", self.base_url);
-        let request = self.client.get(&url);
+        let url = format!("{}/api-key/validate", self.inner.base_url);
+        let request = self.inner.client.get(&url);
         let request = self.add_api_key_header(request);
 
-        let response = request.send().await?;
-        let status = response.status();
+        let reply = self.execute(request).await?;
+
+        let status = reply.status;
 
         match status.as_u16() {
             200 => Ok(()),
             401 => Err(Error::InvalidApiKey),
+            429 => Err(Error::RateLimited(reply.retry_after)),
             500 => Err(Error::ServerError),
             _ => Err(Error::Api(
                 status.as_u16(),
@@ -378,6 +456,91 @@ impl Client {
         }
     }
 
+    /// Get the Steam64 ID of a player, resolving it through their profile
+    /// if it is not known yet
+    ///
+    /// Every fetched profile records its Steam64 ID <-> Leetify ID pair on
+    /// the client (shared between clones), so repeated lookups are free.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Id};
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let steam64_id = client
+    ///     .resolve_steam64_id(Id::Leetify("5ea07280-2399-4c7e-88ab-f2f7db0c449f".into()))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn resolve_steam64_id(&self, id: impl Into<Id>) -> Result<Steam64Id, Error> {
+        let id = id.into();
+        if let Id::Steam64(steam64_id) = &id {
+            return Ok(steam64_id.clone());
+        }
+        if let Id::Leetify(leetify_id) = &id
+            && let Some(steam64_id) = self.known_steam64_id(leetify_id)
+        {
+            return Ok(steam64_id);
+        }
+        Ok(self.get_profile(id).await?.steam64_id.into())
+    }
+
+    /// Get the Leetify ID of a player, resolving it through their profile
+    /// if it is not known yet
+    ///
+    /// Returns `None` if the player has no Leetify account.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Id};
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let leetify_id = client
+    ///     .resolve_leetify_id(Id::Steam64("76561198283431555".into()))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn resolve_leetify_id(&self, id: impl Into<Id>) -> Result<Option<LeetifyId>, Error> {
+        let id = id.into();
+        if let Id::Leetify(leetify_id) = &id {
+            return Ok(Some(leetify_id.clone()));
+        }
+        if let Id::Steam64(steam64_id) = &id
+            && let Some(leetify_id) = self.known_leetify_id(steam64_id)
+        {
+            return Ok(Some(leetify_id));
+        }
+        Ok(self.get_profile(id).await?.id.map(LeetifyId::from))
+    }
+
+    /// Look up the Steam64 ID of a Leetify ID without making a request
+    pub fn known_steam64_id(&self, id: &LeetifyId) -> Option<Steam64Id> {
+        self.ids().steam64_by_leetify.get(id).cloned()
+    }
+
+    /// Look up the Leetify ID of a Steam64 ID without making a request
+    pub fn known_leetify_id(&self, id: &Steam64Id) -> Option<LeetifyId> {
+        self.ids().leetify_by_steam64.get(id).cloned()
+    }
+
+    /// Record that a Steam64 ID and Leetify ID belong to the same player
+    ///
+    /// Useful for seeding the mapping from persisted data.
+    pub fn remember_ids(&self, steam64_id: Steam64Id, leetify_id: LeetifyId) {
+        let mut ids = self.ids();
+        ids.steam64_by_leetify
+            .insert(leetify_id.clone(), steam64_id.clone());
+        ids.leetify_by_steam64.insert(steam64_id, leetify_id);
+    }
+
+    fn ids(&self) -> MutexGuard<'_, IdMap> {
+        self.inner.ids.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     fn build_profile_query_params(&self, id: &Id) -> Vec<(&'static str, String)> {
         match id {
             Id::Steam64(id) => {
@@ -390,24 +553,41 @@ impl Client {
     }
 
     fn add_api_key_header(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
-        if let Some(ref api_key) = self.api_key {
+        if let Some(ref api_key) = self.inner.api_key {
             request.header(API_KEY_HEADER, api_key.as_str())
         } else {
             request
         }
     }
 
-    async fn handle_response<T>(&self, response: reqwest::Response) -> Result<T, Error>
+    /// Send a request, or answer it from the cassette when a [`Vcr`] is
+    /// attached
+    ///
+    /// Every API request goes through here.
+    async fn execute(&self, request: reqwest::RequestBuilder) -> Result<Reply, Error> {
+        #[cfg(feature = "vcr")]
+        if let Some(vcr) = &self.inner.vcr {
+            return vcr.execute(&self.inner.client, request).await;
+        }
+
+        Reply::read(request.send().await?).await
+    }
+
+    fn handle_response<T>(&self, reply: Reply) -> Result<T, Error>
     where
         T: serde::de::DeserializeOwned,
     {
-        let status = response.status();
-        let response_text = response.text().await?;
+        let Reply {
+            status,
+            retry_after,
+            body: response_text,
+        } = reply;
 
         if !status.is_success() {
             let status_code = status.as_u16();
             return match status_code {
                 401 => Err(Error::InvalidApiKey),
+                429 => Err(Error::RateLimited(retry_after)),
                 500 => Err(Error::ServerError),
                 _ => Err(Error::Api(status_code, response_text)),
             };
@@ -431,6 +611,37 @@ impl Client {
     }
 }
 
+/// Status, `Retry-After` delay and body of an API response
+pub(crate) struct Reply {
+    pub(crate) status: reqwest::StatusCode,
+    pub(crate) retry_after: Option<Duration>,
+    pub(crate) body: String,
+}
+
+impl Reply {
+    /// Read the status, `Retry-After` header and body of a response
+    pub(crate) async fn read(response: reqwest::Response) -> Result<Self, Error> {
+        Ok(Self {
+            status: response.status(),
+            retry_after: retry_after(&response),
+            body: response.text().await?,
+        })
+    }
+}
+
+/// Parse the `Retry-After` header (in seconds) of a response
+pub(crate) fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
 impl Default for Client {
     fn default() -> Self {
         Self::new()
@@ -485,8 +696,35 @@ mod tests {
             .build()
             .unwrap();
 
-        assert_eq!(client.base_url, "https://test.example.com");
-        assert_eq!(client.api_key, Some("test-key".to_string()));
+        assert_eq!(client.inner.base_url, "https://test.example.com");
+        assert_eq!(client.inner.api_key, Some("test-key".to_string()));
+    }
+
+    #[test]
+    fn test_client_clone_shares_state() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<Client>();
+
+        let client = ClientBuilder::new().api_key("test-key").build().unwrap();
+        let clone = client.clone();
+        assert!(Arc::ptr_eq(&client.inner, &clone.inner));
+    }
+
+    #[test]
+    fn test_id_mapping() {
+        let client = Client::new();
+        let steam64_id: Steam64Id = "76561198000000000".into();
+        let leetify_id: LeetifyId = "5ea07280-2399-4c7e-88ab-f2f7db0c449f".into();
+        assert!(client.known_leetify_id(&steam64_id).is_none());
+
+        client
+            .clone()
+            .remember_ids(steam64_id.clone(), leetify_id.clone());
+        assert_eq!(
+            client.known_leetify_id(&steam64_id),
+            Some(leetify_id.clone())
+        );
+        assert_eq!(client.known_steam64_id(&leetify_id), Some(steam64_id));
     }
 
     #[test]
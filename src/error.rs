@@ -1,6 +1,12 @@
+use std::time::Duration;
 use thiserror::Error;
 
+/// Errors returned by this crate
+///
+/// New variants may be added in minor releases, and some only exist with
+/// their feature enabled, so matches need a wildcard arm.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
     #[error("HTTP request error: {0}")]
     Http(#[from] reqwest::Error),
@@ -17,6 +23,10 @@ pub enum Error {
     #[error("Server error (500)")]
     ServerError,
 
+    /// The API answered with 429; holds the `Retry-After` delay if one was sent
+    #[error("Rate limit exceeded (429)")]
+    RateLimited(Option<Duration>),
+
     #[error("Missing required parameter: {0}")]
     MissingParameter(String),
 
@@ -25,4 +35,41 @@ pub enum Error {
 
     #[error("Invalid data source: {0}")]
     InvalidDataSource(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Failure of a custom [`Store`](crate::store::Store) backend
+    #[error("Storage error: {0}")]
+    Storage(Box<dyn std::error::Error + Send + Sync>),
+
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[cfg(feature = "arrow")]
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] arrow::error::ArrowError),
+
+    #[cfg(feature = "arrow")]
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[cfg(feature = "report")]
+    #[error("Template error: {0}")]
+    Template(#[from] minijinja::Error),
+
+    #[cfg(feature = "export")]
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    /// A webhook answered with a non-success status
+    #[cfg(feature = "notify")]
+    #[error("Webhook error (status {0}): {1}")]
+    Webhook(u16, String),
+
+    /// A request could not be answered from a VCR cassette
+    #[cfg(feature = "vcr")]
+    #[error("VCR error: {0}")]
+    Vcr(String),
 }
@@ -0,0 +1,763 @@
+//! Flat exports of matches and profiles as CSV or JSON Lines
+//!
+//! Matches are exported as one row per participant ([`MATCH_PLAYER_COLUMNS`]),
+//! profiles as one wide row per profile ([`PROFILE_COLUMNS`]). Column order is
+//! part of the format: new columns are only ever appended.
+
+use crate::error::Error;
+use crate::types::{MatchDetailsResponse, PlayerStats, ProfileResponse};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::io::Write;
+
+/// Output format of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Comma-separated values with a header row
+    Csv,
+    /// One JSON object per line (NDJSON)
+    JsonLines,
+}
+
+/// A single exported value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Timestamp(DateTime<Utc>),
+}
+
+impl Value {
+    /// Text representation used in CSV cells; null is an empty cell and
+    /// timestamps are RFC 3339
+    pub fn to_csv_field(&self) -> String {
+        match self {
+            Value::Null => String::new(),
+            Value::Bool(b) => b.to_string(),
+            Value::Integer(i) => i.to_string(),
+            Value::Float(f) => f.to_string(),
+            Value::Text(s) => s.clone(),
+            Value::Timestamp(t) => t.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Integer(i) => serializer.serialize_i64(*i),
+            Value::Float(f) => serializer.serialize_f64(*f),
+            Value::Text(s) => serializer.serialize_str(s),
+            Value::Timestamp(_) => serializer.serialize_str(&self.to_csv_field()),
+        }
+    }
+}
+
+impl From<&u32> for Value {
+    fn from(value: &u32) -> Self {
+        Value::Integer(*value as i64)
+    }
+}
+
+impl From<&f64> for Value {
+    fn from(value: &f64) -> Self {
+        Value::Float(*value)
+    }
+}
+
+impl From<&bool> for Value {
+    fn from(value: &bool) -> Self {
+        Value::Bool(*value)
+    }
+}
+
+impl From<&String> for Value {
+    fn from(value: &String) -> Self {
+        Value::Text(value.clone())
+    }
+}
+
+impl From<&DateTime<Utc>> for Value {
+    fn from(value: &DateTime<Utc>) -> Self {
+        Value::Timestamp(*value)
+    }
+}
+
+impl From<&Option<u32>> for Value {
+    fn from(value: &Option<u32>) -> Self {
+        value.as_ref().map_or(Value::Null, Value::from)
+    }
+}
+
+impl From<&Option<f64>> for Value {
+    fn from(value: &Option<f64>) -> Self {
+        value.as_ref().map_or(Value::Null, Value::from)
+    }
+}
+
+impl From<&Option<String>> for Value {
+    fn from(value: &Option<String>) -> Self {
+        value.as_ref().map_or(Value::Null, Value::from)
+    }
+}
+
+impl From<&Option<DateTime<Utc>>> for Value {
+    fn from(value: &Option<DateTime<Utc>>) -> Self {
+        value.as_ref().map_or(Value::Null, Value::from)
+    }
+}
+
+/// A documented export column
+#[derive(Clone, Copy)]
+pub struct Column<F> {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Extracts the value of this column from a source row
+    pub value: F,
+}
+
+impl<F> std::fmt::Debug for Column<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Column")
+            .field("name", &self.name)
+            .field("description", &self.description)
+            .finish()
+    }
+}
+
+/// Column of a match export, computed from a match and one participant
+pub type MatchPlayerColumn = Column<fn(&MatchDetailsResponse, &PlayerStats) -> Value>;
+
+/// Column of a profile export
+pub type ProfileColumn = Column<fn(&ProfileResponse) -> Value>;
+
+macro_rules! stat {
+    ($field:ident, $description:literal) => {
+        Column {
+            name: stringify!($field),
+            description: $description,
+            value: |_, stats| Value::from(&stats.$field),
+        }
+    };
+}
+
+macro_rules! profile {
+    ($name:literal, $($path:ident).+, $description:literal) => {
+        Column {
+            name: $name,
+            description: $description,
+            value: |profile| Value::from(&profile.$($path).+),
+        }
+    };
+}
+
+/// Score of the participant's team and of the other team
+fn scores(details: &MatchDetailsResponse, stats: &PlayerStats) -> (Value, Value) {
+    let [a, b] = &details.team_scores;
+    if a.team_number == stats.initial_team_number {
+        (Value::from(&a.score), Value::from(&b.score))
+    } else if b.team_number == stats.initial_team_number {
+        (Value::from(&b.score), Value::from(&a.score))
+    } else {
+        (Value::Null, Value::Null)
+    }
+}
+
+/// Columns of a match export, one row per participant
+pub const MATCH_PLAYER_COLUMNS: &[MatchPlayerColumn] = &[
+    Column {
+        name: "match_id",
+        description: "Leetify match ID",
+        value: |details, _| Value::from(&details.id),
+    },
+    Column {
+        name: "finished_at",
+        description: "When the match finished (RFC 3339, UTC)",
+        value: |details, _| Value::from(&details.finished_at),
+    },
+    Column {
+        name: "data_source",
+        description: "Where the match was played, e.g. faceit or matchmaking",
+        value: |details, _| Value::from(&details.data_source),
+    },
+    Column {
+        name: "data_source_match_id",
+        description: "Match ID at the data source",
+        value: |details, _| Value::from(&details.data_source_match_id),
+    },
+    Column {
+        name: "map_name",
+        description: "Map, e.g. de_nuke",
+        value: |details, _| Value::from(&details.map_name),
+    },
+    Column {
+        name: "has_banned_player",
+        description: "Whether any participant is banned",
+        value: |details, _| Value::from(&details.has_banned_player),
+    },
+    Column {
+        name: "team1_number",
+        description: "Team number of the first team score",
+        value: |details, _| Value::from(&details.team_scores[0].team_number),
+    },
+    Column {
+        name: "team1_score",
+        description: "Rounds won by the first team",
+        value: |details, _| Value::from(&details.team_scores[0].score),
+    },
+    Column {
+        name: "team2_number",
+        description: "Team number of the second team score",
+        value: |details, _| Value::from(&details.team_scores[1].team_number),
+    },
+    Column {
+        name: "team2_score",
+        description: "Rounds won by the second team",
+        value: |details, _| Value::from(&details.team_scores[1].score),
+    },
+    Column {
+        name: "team_score",
+        description: "Rounds won by the participant's team",
+        value: |details, stats| scores(details, stats).0,
+    },
+    Column {
+        name: "opponent_score",
+        description: "Rounds won by the other team",
+        value: |details, stats| scores(details, stats).1,
+    },
+    stat!(steam64_id, "Participant's Steam64 ID"),
+    stat!(name, "Participant's name"),
+    stat!(initial_team_number, "Team the participant started on"),
+    stat!(mvps, "MVP awards"),
+    stat!(score, "In-game score"),
+    stat!(total_kills, "Kills"),
+    stat!(total_deaths, "Deaths"),
+    stat!(total_assists, "Assists"),
+    stat!(kd_ratio, "Kills per death"),
+    stat!(total_damage, "Damage dealt"),
+    stat!(dpr, "Damage per round"),
+    stat!(total_hs_kills, "Headshot kills"),
+    stat!(leetify_rating, "Leetify rating; empty if not rated"),
+    stat!(
+        ct_leetify_rating,
+        "Leetify rating on CT side; empty if not rated"
+    ),
+    stat!(
+        t_leetify_rating,
+        "Leetify rating on T side; empty if not rated"
+    ),
+    stat!(rounds_count, "Rounds played"),
+    stat!(rounds_won, "Rounds won"),
+    stat!(rounds_lost, "Rounds lost"),
+    stat!(rounds_survived, "Rounds survived"),
+    stat!(rounds_survived_percentage, "Rounds survived (%)"),
+    stat!(multi1k, "Rounds with exactly 1 kill"),
+    stat!(multi2k, "Rounds with exactly 2 kills"),
+    stat!(multi3k, "Rounds with exactly 3 kills"),
+    stat!(multi4k, "Rounds with exactly 4 kills"),
+    stat!(multi5k, "Rounds with 5 kills"),
+    stat!(preaim, "Crosshair placement error (degrees)"),
+    stat!(reaction_time, "Time to damage (seconds)"),
+    stat!(accuracy, "Share of shots that hit"),
+    stat!(
+        accuracy_enemy_spotted,
+        "Accuracy while an enemy was spotted"
+    ),
+    stat!(accuracy_head, "Share of hits that hit the head"),
+    stat!(spray_accuracy, "Accuracy while spraying"),
+    stat!(shots_fired, "Shots fired"),
+    stat!(
+        shots_fired_enemy_spotted,
+        "Shots fired while an enemy was spotted"
+    ),
+    stat!(shots_hit_enemy_spotted, "Hits while an enemy was spotted"),
+    stat!(shots_hit_foe, "Hits on enemies"),
+    stat!(shots_hit_foe_head, "Headshot hits on enemies"),
+    stat!(shots_hit_friend, "Hits on teammates"),
+    stat!(shots_hit_friend_head, "Headshot hits on teammates"),
+    stat!(
+        counter_strafing_shots_all,
+        "Shots evaluated for counter-strafing"
+    ),
+    stat!(
+        counter_strafing_shots_good,
+        "Shots with good counter-strafing"
+    ),
+    stat!(
+        counter_strafing_shots_bad,
+        "Shots with bad counter-strafing"
+    ),
+    stat!(
+        counter_strafing_shots_good_ratio,
+        "Share of shots with good counter-strafing"
+    ),
+    stat!(utility_on_death_avg, "Unused utility value at death"),
+    stat!(he_thrown, "HE grenades thrown"),
+    stat!(he_foes_damage_avg, "Average HE damage to enemies"),
+    stat!(he_friends_damage_avg, "Average HE damage to teammates"),
+    stat!(molotov_thrown, "Molotovs and incendiaries thrown"),
+    stat!(smoke_thrown, "Smokes thrown"),
+    stat!(flashbang_thrown, "Flashbangs thrown"),
+    stat!(flashbang_hit_foe, "Enemies flashed"),
+    stat!(
+        flashbang_hit_foe_avg_duration,
+        "Average enemy blind duration (seconds)"
+    ),
+    stat!(flashbang_hit_friend, "Teammates flashed"),
+    stat!(flashbang_leading_to_kill, "Flashbangs leading to a kill"),
+    stat!(flash_assist, "Flash assists"),
+    stat!(
+        trade_kill_opportunities,
+        "Opportunities to trade a teammate"
+    ),
+    stat!(trade_kill_attempts, "Attempted trade kills"),
+    stat!(trade_kills_succeed, "Successful trade kills"),
+    stat!(
+        trade_kill_attempts_percentage,
+        "Trade opportunities attempted (%)"
+    ),
+    stat!(
+        trade_kills_success_percentage,
+        "Trade attempts that succeeded (%)"
+    ),
+    stat!(
+        trade_kill_opportunities_per_round,
+        "Trade opportunities per round"
+    ),
+    stat!(
+        traded_death_opportunities,
+        "Deaths that could have been traded"
+    ),
+    stat!(traded_death_attempts, "Deaths a teammate tried to trade"),
+    stat!(traded_deaths_succeed, "Deaths that were traded"),
+    stat!(
+        traded_death_attempts_percentage,
+        "Tradeable deaths attempted (%)"
+    ),
+    stat!(
+        traded_deaths_success_percentage,
+        "Trade attempts on deaths that succeeded (%)"
+    ),
+    stat!(
+        traded_deaths_opportunities_per_round,
+        "Tradeable deaths per round"
+    ),
+];
+
+/// Columns of a profile export, one row per profile
+pub const PROFILE_COLUMNS: &[ProfileColumn] = &[
+    profile!("steam64_id", steam64_id, "Steam64 ID"),
+    profile!("leetify_id", id, "Leetify user ID; empty if unknown"),
+    profile!("name", name, "Player name"),
+    profile!("privacy_mode", privacy_mode, "Profile privacy mode"),
+    profile!("winrate", winrate, "Share of matches won"),
+    profile!("total_matches", total_matches, "Matches played"),
+    profile!(
+        "first_match_date",
+        first_match_date,
+        "First tracked match (RFC 3339, UTC)"
+    ),
+    Column {
+        name: "banned",
+        description: "Whether any platform ban is recorded",
+        value: |profile| Value::Bool(!profile.bans.is_empty()),
+    },
+    Column {
+        name: "ban_platforms",
+        description: "Platforms with a ban, separated by ';'",
+        value: |profile| {
+            let platforms: Vec<_> = profile.bans.iter().map(|b| b.platform.as_str()).collect();
+            Value::Text(platforms.join(";"))
+        },
+    },
+    profile!("rank_leetify", ranks.leetify, "Leetify rating"),
+    profile!("rank_premier", ranks.premier, "Premier rating"),
+    profile!("rank_faceit", ranks.faceit, "FACEIT level"),
+    profile!("rank_faceit_elo", ranks.faceit_elo, "FACEIT Elo"),
+    profile!("rank_wingman", ranks.wingman, "Wingman rank"),
+    profile!("rank_renown", ranks.renown, "Renown rank"),
+    Column {
+        name: "rank_competitive",
+        description: "Competitive ranks as map:rank pairs, separated by ';'",
+        value: |profile| {
+            let ranks: Vec<_> = profile
+                .ranks
+                .competitive
+                .iter()
+                .map(|r| format!("{}:{}", r.map_name, r.rank))
+                .collect();
+            Value::Text(ranks.join(";"))
+        },
+    },
+    profile!("rating_aim", rating.aim, "Aim rating"),
+    profile!(
+        "rating_positioning",
+        rating.positioning,
+        "Positioning rating"
+    ),
+    profile!("rating_utility", rating.utility, "Utility rating"),
+    profile!("rating_clutch", rating.clutch, "Clutch rating"),
+    profile!("rating_opening", rating.opening, "Opening duel rating"),
+    profile!(
+        "rating_ct_leetify",
+        rating.ct_leetify,
+        "Leetify rating on CT side"
+    ),
+    profile!(
+        "rating_t_leetify",
+        rating.t_leetify,
+        "Leetify rating on T side"
+    ),
+    profile!(
+        "stats_accuracy_enemy_spotted",
+        stats.accuracy_enemy_spotted,
+        "Accuracy while an enemy was spotted"
+    ),
+    profile!(
+        "stats_accuracy_head",
+        stats.accuracy_head,
+        "Share of hits that hit the head"
+    ),
+    profile!(
+        "stats_counter_strafing_good_shots_ratio",
+        stats.counter_strafing_good_shots_ratio,
+        "Share of shots with good counter-strafing"
+    ),
+    profile!(
+        "stats_ct_opening_aggression_success_rate",
+        stats.ct_opening_aggression_success_rate,
+        "Aggressive CT opening duels won (%)"
+    ),
+    profile!(
+        "stats_ct_opening_duel_success_percentage",
+        stats.ct_opening_duel_success_percentage,
+        "CT opening duels won (%)"
+    ),
+    profile!(
+        "stats_flashbang_hit_foe_avg_duration",
+        stats.flashbang_hit_foe_avg_duration,
+        "Average enemy blind duration (seconds)"
+    ),
+    profile!(
+        "stats_flashbang_hit_foe_per_flashbang",
+        stats.flashbang_hit_foe_per_flashbang,
+        "Enemies flashed per flashbang"
+    ),
+    profile!(
+        "stats_flashbang_hit_friend_per_flashbang",
+        stats.flashbang_hit_friend_per_flashbang,
+        "Teammates flashed per flashbang"
+    ),
+    profile!(
+        "stats_flashbang_leading_to_kill",
+        stats.flashbang_leading_to_kill,
+        "Flashbangs leading to a kill"
+    ),
+    profile!(
+        "stats_flashbang_thrown",
+        stats.flashbang_thrown,
+        "Flashbangs thrown per match"
+    ),
+    profile!(
+        "stats_he_foes_damage_avg",
+        stats.he_foes_damage_avg,
+        "Average HE damage to enemies"
+    ),
+    profile!(
+        "stats_he_friends_damage_avg",
+        stats.he_friends_damage_avg,
+        "Average HE damage to teammates"
+    ),
+    profile!(
+        "stats_preaim",
+        stats.preaim,
+        "Crosshair placement error (degrees)"
+    ),
+    profile!(
+        "stats_reaction_time_ms",
+        stats.reaction_time_ms,
+        "Time to damage (milliseconds)"
+    ),
+    profile!(
+        "stats_spray_accuracy",
+        stats.spray_accuracy,
+        "Accuracy while spraying"
+    ),
+    profile!(
+        "stats_t_opening_aggression_success_rate",
+        stats.t_opening_aggression_success_rate,
+        "Aggressive T opening duels won (%)"
+    ),
+    profile!(
+        "stats_t_opening_duel_success_percentage",
+        stats.t_opening_duel_success_percentage,
+        "T opening duels won (%)"
+    ),
+    profile!(
+        "stats_traded_deaths_success_percentage",
+        stats.traded_deaths_success_percentage,
+        "Deaths that were traded (%)"
+    ),
+    profile!(
+        "stats_trade_kill_opportunities_per_round",
+        stats.trade_kill_opportunities_per_round,
+        "Trade opportunities per round"
+    ),
+    profile!(
+        "stats_trade_kills_success_percentage",
+        stats.trade_kills_success_percentage,
+        "Trade attempts that succeeded (%)"
+    ),
+    profile!(
+        "stats_utility_on_death_avg",
+        stats.utility_on_death_avg,
+        "Unused utility value at death"
+    ),
+];
+
+/// Ordered row of named values, serialized as a JSON object
+struct Row<'a>(Vec<(&'a str, Value)>);
+
+impl Serialize for Row<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (name, value) in &self.0 {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Writes rows in a [`Format`]
+enum RowWriter<W: Write> {
+    Csv(Box<csv::Writer<W>>),
+    JsonLines(W),
+}
+
+impl<W: Write> RowWriter<W> {
+    fn new(writer: W, format: Format, header: &[&str]) -> Result<Self, Error> {
+        Ok(match format {
+            Format::Csv => {
+                let mut csv = csv::Writer::from_writer(writer);
+                csv.write_record(header)?;
+                RowWriter::Csv(Box::new(csv))
+            }
+            Format::JsonLines => RowWriter::JsonLines(writer),
+        })
+    }
+
+    fn write(&mut self, row: Row<'_>) -> Result<(), Error> {
+        match self {
+            RowWriter::Csv(csv) => {
+                csv.write_record(row.0.iter().map(|(_, value)| value.to_csv_field()))?;
+            }
+            RowWriter::JsonLines(writer) => {
+                serde_json::to_writer(&mut *writer, &row)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        match self {
+            RowWriter::Csv(mut csv) => csv.flush()?,
+            RowWriter::JsonLines(mut writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+}
+
+/// Flatten a match into one row of [`MATCH_PLAYER_COLUMNS`] values per
+/// participant
+pub fn match_player_rows(details: &MatchDetailsResponse) -> Vec<Vec<Value>> {
+    details
+        .stats
+        .iter()
+        .map(|stats| {
+            MATCH_PLAYER_COLUMNS
+                .iter()
+                .map(|column| (column.value)(details, stats))
+                .collect()
+        })
+        .collect()
+}
+
+/// Flatten a profile into a row of [`PROFILE_COLUMNS`] values
+pub fn profile_row(profile: &ProfileResponse) -> Vec<Value> {
+    PROFILE_COLUMNS
+        .iter()
+        .map(|column| (column.value)(profile))
+        .collect()
+}
+
+/// Write matches with one row per participant
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::{Client, Id};
+/// # use leetify::export::{self, Format};
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Client::new();
+/// let matches = client
+///     .get_profile_matches(Id::Steam64("76561198283431555".into()))
+///     .await?;
+///
+/// let file = std::fs::File::create("matches.csv")?;
+/// export::write_matches(file, &matches, Format::Csv)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_matches<'a, W: Write>(
+    writer: W,
+    matches: impl IntoIterator<Item = &'a MatchDetailsResponse>,
+    format: Format,
+) -> Result<(), Error> {
+    let header: Vec<_> = MATCH_PLAYER_COLUMNS.iter().map(|c| c.name).collect();
+    let mut rows = RowWriter::new(writer, format, &header)?;
+    for details in matches {
+        for values in match_player_rows(details) {
+            rows.write(Row(header.iter().copied().zip(values).collect()))?;
+        }
+    }
+    rows.finish()
+}
+
+/// Write profiles with one row per profile
+pub fn write_profiles<'a, W: Write>(
+    writer: W,
+    profiles: impl IntoIterator<Item = &'a ProfileResponse>,
+    format: Format,
+) -> Result<(), Error> {
+    let header: Vec<_> = PROFILE_COLUMNS.iter().map(|c| c.name).collect();
+    let mut rows = RowWriter::new(writer, format, &header)?;
+    for profile in profiles {
+        rows.write(Row(header
+            .iter()
+            .copied()
+            .zip(profile_row(profile))
+            .collect()))?;
+    }
+    rows.finish()
+}
+
+/// Document columns as a Markdown table of names and descriptions
+pub fn column_docs<F>(columns: &[Column<F>]) -> String {
+    let mut docs = String::from("| Column | Description |\n|---|---|\n");
+    for column in columns {
+        docs.push_str(&format!("| `{}` | {} |\n", column.name, column.description));
+    }
+    docs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PlatformBanInfo, Ranks, TeamScore};
+    use std::collections::HashSet;
+
+    fn game() -> MatchDetailsResponse {
+        let player = |steam64_id: &str, team: u32| PlayerStats {
+            steam64_id: steam64_id.to_string(),
+            initial_team_number: team,
+            total_kills: 20,
+            leetify_rating: (team == 2).then_some(0.05),
+            ..Default::default()
+        };
+        MatchDetailsResponse {
+            id: "m1".to_string(),
+            finished_at: DateTime::from_timestamp(0, 0).unwrap(),
+            data_source: "faceit".to_string(),
+            map_name: "de_nuke".to_string(),
+            team_scores: [
+                TeamScore {
+                    team_number: 2,
+                    score: 13,
+                },
+                TeamScore {
+                    team_number: 3,
+                    score: 7,
+                },
+            ],
+            stats: vec![player("a", 2), player("b", 3)],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_column_names_are_unique() {
+        let names: HashSet<_> = MATCH_PLAYER_COLUMNS.iter().map(|c| c.name).collect();
+        assert_eq!(names.len(), MATCH_PLAYER_COLUMNS.len());
+        let names: HashSet<_> = PROFILE_COLUMNS.iter().map(|c| c.name).collect();
+        assert_eq!(names.len(), PROFILE_COLUMNS.len());
+    }
+
+    #[test]
+    fn test_matches_csv() {
+        let mut out = Vec::new();
+        write_matches(&mut out, &[game()], Format::Csv).unwrap();
+
+        let mut reader = csv::Reader::from_reader(out.as_slice());
+        let header: Vec<_> = reader.headers().unwrap().iter().map(String::from).collect();
+        assert_eq!(&header[..3], ["match_id", "finished_at", "data_source"]);
+
+        let rows: Vec<_> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 2);
+        let field = |row: usize, name: &str| {
+            let index = header.iter().position(|h| h == name).unwrap();
+            rows[row][index].to_string()
+        };
+        assert_eq!(field(0, "finished_at"), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            (field(0, "team_score"), field(0, "opponent_score")),
+            ("13".into(), "7".into())
+        );
+        assert_eq!(
+            (field(1, "team_score"), field(1, "opponent_score")),
+            ("7".into(), "13".into())
+        );
+        assert_eq!(field(0, "leetify_rating"), "0.05");
+        assert_eq!(field(1, "leetify_rating"), "");
+    }
+
+    #[test]
+    fn test_profiles_json_lines() {
+        let profile = ProfileResponse {
+            steam64_id: "a".to_string(),
+            ranks: Ranks {
+                premier: Some(15_000),
+                ..Default::default()
+            },
+            bans: vec![PlatformBanInfo {
+                platform: "vac".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut out = Vec::new();
+        write_profiles(&mut out, [&profile, &profile], Format::JsonLines).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), 2);
+
+        let first = text.lines().next().unwrap();
+        assert!(first.starts_with(r#"{"steam64_id":"a","leetify_id":null,"#));
+        let row: serde_json::Value = serde_json::from_str(first).unwrap();
+        assert_eq!(row["rank_premier"], 15_000);
+        assert_eq!(row["ban_platforms"], "vac");
+        assert_eq!(row.as_object().unwrap().len(), PROFILE_COLUMNS.len());
+    }
+
+    #[test]
+    fn test_column_docs() {
+        let docs = column_docs(PROFILE_COLUMNS);
+        assert_eq!(docs.lines().count(), PROFILE_COLUMNS.len() + 2);
+        assert!(docs.contains("| `rank_premier` | Premier rating |"));
+    }
+}
@@ -0,0 +1,289 @@
+//! Generation of random but internally consistent response data
+//!
+//! Shared by the `proptest` strategies and the `arbitrary` impls: both only
+//! supply numbers through [`Source`], and everything that has to agree (e.g.
+//! a player's rounds with the team score, FACEIT level with Elo, a profile's
+//! recent matches with its match history) is derived here.
+
+use crate::builders::{self, Line, faceit_level, lobby_player, steam64_id};
+use crate::types::{
+    CompetitiveRank, MatchDetailsResponse, PlatformBanInfo, PlayerStats, ProfileResponse, Ranks,
+    Rating, RecentMatch, RecentTeammate, Stats, TeamScore,
+};
+use chrono::{DateTime, Duration, Utc};
+
+const MAPS: [&str; 9] = [
+    "de_ancient",
+    "de_anubis",
+    "de_dust2",
+    "de_inferno",
+    "de_mirage",
+    "de_nuke",
+    "de_overpass",
+    "de_train",
+    "de_vertigo",
+];
+
+const DATA_SOURCES: [&str; 2] = ["matchmaking", "faceit"];
+
+const BAN_PLATFORMS: [&str; 2] = ["faceit", "steam"];
+
+/// Supplier of random numbers
+pub(crate) trait Source {
+    /// A number in `lo..=hi`
+    fn int(&mut self, lo: u32, hi: u32) -> u32;
+
+    fn pick<'a>(&mut self, values: &[&'a str]) -> &'a str {
+        values[self.int(0, values.len() as u32 - 1) as usize]
+    }
+
+    fn flag(&mut self) -> bool {
+        self.int(0, 1) == 1
+    }
+
+    /// A number in `lo..=hi` divided by `scale`
+    fn float(&mut self, lo: u32, hi: u32, scale: f64) -> f64 {
+        self.int(lo, hi) as f64 / scale
+    }
+}
+
+/// Finish time of the newest generated match
+fn latest() -> DateTime<Utc> {
+    DateTime::from_timestamp(1_735_732_800, 0).unwrap_or_default()
+}
+
+fn timestamp(source: &mut impl Source) -> DateTime<Utc> {
+    latest()
+        - Duration::days(source.int(0, 3_650) as i64)
+        - Duration::seconds(source.int(0, 86_399) as i64)
+}
+
+/// A version 4 UUID whose last digits are `salt`, so IDs generated with
+/// different salts never collide
+fn uuid(source: &mut impl Source, salt: u32) -> String {
+    format!(
+        "{:08x}-{:04x}-4{:03x}-8{:03x}-{:04x}{:08x}",
+        source.int(0, u32::MAX - 1),
+        source.int(0, 0xffff),
+        source.int(0, 0xfff),
+        source.int(0, 0xfff),
+        source.int(0, 0xffff),
+        salt,
+    )
+}
+
+fn line(source: &mut impl Source, won: u32, lost: u32) -> Line {
+    let kills = source.int(0, 45);
+    let shots_fired = source.int(0, 1_000);
+    Line {
+        won,
+        lost,
+        kills,
+        deaths: source.int(0, 30),
+        assists: source.int(0, 15),
+        hs_kills: kills * source.int(0, 100) / 100,
+        shots_fired,
+        shots_hit: shots_fired * source.int(0, 100) / 100,
+        damage: kills * source.int(40, 150) + source.int(0, 600),
+        rating: source.float(0, 400, 1_000.0) - 0.2,
+    }
+}
+
+pub(crate) fn player_stats(source: &mut impl Source) -> PlayerStats {
+    let (won, lost) = (source.int(0, 16), source.int(0, 16));
+    let team = source.int(2, 3);
+    let n = source.int(1, 1_000_000) as u64;
+    let line = line(source, won, lost);
+    let mut stats = lobby_player(n, team, line);
+    stats.reaction_time = source.float(300, 900, 1_000.0);
+    stats.preaim = source.float(20, 300, 10.0);
+    stats.spray_accuracy = source.float(0, 100, 100.0);
+    stats
+}
+
+pub(crate) fn team_score(source: &mut impl Source) -> TeamScore {
+    TeamScore {
+        team_number: source.int(2, 3),
+        score: source.int(0, 16),
+    }
+}
+
+/// A match of 2 to 10 players; with `player` set, the first player is
+/// `steam64_id(0)` and the match ID ends with `salt`
+fn lobby(source: &mut impl Source, player: bool, salt: u32) -> MatchDetailsResponse {
+    let id = uuid(source, salt);
+    let (team2, team3) = (source.int(0, 16), source.int(0, 16));
+    let count = source.int(2, 10) as u64;
+    // Multiples of 10 never collide with the fixed player 0
+    let base = source.int(1, 100_000) as u64 * 10;
+
+    let stats = (0..count)
+        .map(|i| {
+            let (team, won, lost) = if i % 2 == 0 {
+                (2, team2, team3)
+            } else {
+                (3, team3, team2)
+            };
+            let n = if player && i == 0 { 0 } else { base + i };
+            let line = line(source, won, lost);
+            let mut stats = lobby_player(n, team, line);
+            stats.reaction_time = source.float(300, 900, 1_000.0);
+            stats.preaim = source.float(20, 300, 10.0);
+            stats
+        })
+        .collect();
+
+    let data_source = source.pick(&DATA_SOURCES);
+    MatchDetailsResponse {
+        data_source_match_id: format!("{}-{}", data_source, source.int(0, u32::MAX - 1)),
+        id,
+        finished_at: timestamp(source),
+        data_source: data_source.to_string(),
+        map_name: source.pick(&MAPS).to_string(),
+        has_banned_player: source.int(0, 9) == 0,
+        team_scores: [
+            TeamScore {
+                team_number: 2,
+                score: team2,
+            },
+            TeamScore {
+                team_number: 3,
+                score: team3,
+            },
+        ],
+        stats,
+    }
+}
+
+pub(crate) fn match_details(source: &mut impl Source) -> MatchDetailsResponse {
+    lobby(source, false, 0)
+}
+
+pub(crate) fn competitive_rank(source: &mut impl Source) -> CompetitiveRank {
+    CompetitiveRank {
+        map_name: source.pick(&MAPS).to_string(),
+        rank: source.int(1, 18),
+    }
+}
+
+pub(crate) fn ranks(source: &mut impl Source) -> Ranks {
+    let faceit_elo = source.flag().then(|| source.int(100, 3_500));
+    let faceit = faceit_elo.map(faceit_level);
+    let mut maps: Vec<_> = MAPS.to_vec();
+    let competitive = (0..source.int(0, 3))
+        .map(|_| {
+            let map = maps.remove(source.int(0, maps.len() as u32 - 1) as usize);
+            CompetitiveRank {
+                map_name: map.to_string(),
+                rank: source.int(1, 18),
+            }
+        })
+        .collect();
+
+    Ranks {
+        leetify: source.flag().then(|| source.float(0, 600, 100.0) - 3.0),
+        premier: source.flag().then(|| source.int(1_000, 35_000)),
+        faceit,
+        faceit_elo,
+        wingman: source.flag().then(|| source.int(1, 18)),
+        renown: source.flag().then(|| source.int(0, 20_000)),
+        competitive,
+    }
+}
+
+pub(crate) fn rating(source: &mut impl Source) -> Rating {
+    Rating {
+        aim: source.float(0, 1_000, 10.0),
+        positioning: source.float(0, 1_000, 10.0),
+        utility: source.float(0, 1_000, 10.0),
+        clutch: source.float(0, 300, 1_000.0),
+        opening: source.float(0, 200, 1_000.0) - 0.1,
+        ct_leetify: source.float(0, 100, 1_000.0) - 0.05,
+        t_leetify: source.float(0, 100, 1_000.0) - 0.05,
+    }
+}
+
+pub(crate) fn stats(source: &mut impl Source) -> Stats {
+    Stats {
+        accuracy_enemy_spotted: source.float(0, 1_000, 10.0),
+        accuracy_head: source.float(0, 1_000, 10.0),
+        counter_strafing_good_shots_ratio: source.float(0, 1_000, 10.0),
+        ct_opening_aggression_success_rate: source.float(0, 1_000, 10.0),
+        ct_opening_duel_success_percentage: source.float(0, 1_000, 10.0),
+        t_opening_aggression_success_rate: source.float(0, 1_000, 10.0),
+        t_opening_duel_success_percentage: source.float(0, 1_000, 10.0),
+        spray_accuracy: source.float(0, 1_000, 10.0),
+        traded_deaths_success_percentage: source.float(0, 1_000, 10.0),
+        trade_kills_success_percentage: source.float(0, 1_000, 10.0),
+        flashbang_hit_foe_avg_duration: source.float(0, 50, 10.0),
+        flashbang_hit_foe_per_flashbang: source.float(0, 300, 100.0),
+        flashbang_hit_friend_per_flashbang: source.float(0, 100, 100.0),
+        flashbang_leading_to_kill: source.float(0, 100, 100.0),
+        flashbang_thrown: source.float(0, 200, 10.0),
+        he_foes_damage_avg: source.float(0, 400, 10.0),
+        he_friends_damage_avg: source.float(0, 100, 10.0),
+        preaim: source.float(20, 300, 10.0),
+        reaction_time_ms: source.float(300, 900, 1.0),
+        trade_kill_opportunities_per_round: source.float(0, 100, 100.0),
+        utility_on_death_avg: source.float(0, 5_000, 10.0),
+    }
+}
+
+pub(crate) fn recent_teammate(source: &mut impl Source) -> RecentTeammate {
+    RecentTeammate {
+        steam64_id: steam64_id(source.int(1, 1_000_000) as u64),
+        recent_matches_count: source.int(1, 50),
+    }
+}
+
+pub(crate) fn platform_ban_info(source: &mut impl Source) -> PlatformBanInfo {
+    PlatformBanInfo {
+        platform: source.pick(&BAN_PLATFORMS).to_string(),
+        platform_nickname: format!("player{}", source.int(0, 9_999)),
+        banned_since: timestamp(source),
+    }
+}
+
+pub(crate) fn recent_match(source: &mut impl Source) -> RecentMatch {
+    let details = lobby(source, true, 0);
+    let premier = source.int(1_000, 35_000);
+    // The first player is always part of the lobby
+    builders::recent_match(&details, &steam64_id(0), Some(premier)).unwrap_or_default()
+}
+
+/// A profile of `steam64_id(0)` with up to 5 recent matches, newest first,
+/// ranked with the profile's Premier rating
+pub(crate) fn profile(source: &mut impl Source) -> ProfileResponse {
+    let mut matches: Vec<_> = (0..source.int(0, 5))
+        .map(|i| lobby(source, true, i))
+        .collect();
+    matches.sort_by_key(|m| std::cmp::Reverse(m.finished_at));
+
+    let ranks = ranks(source);
+    let bans: Vec<_> = (0..source.int(0, 1))
+        .map(|_| platform_ban_info(source))
+        .collect();
+    let first_match_date = match matches.last() {
+        Some(oldest) => oldest.finished_at - Duration::days(source.int(0, 1_000) as i64),
+        None => timestamp(source),
+    };
+
+    ProfileResponse::builder()
+        .privacy_mode(if source.int(0, 9) == 0 {
+            "private"
+        } else {
+            "public"
+        })
+        .winrate(source.float(0, 1_000, 1_000.0))
+        .total_matches(source.int(0, 2_000))
+        .first_match_date(first_match_date)
+        .name(format!("player{}", source.int(0, 9_999)))
+        .bans(bans)
+        .steam64_id(steam64_id(0))
+        .id(source.flag().then(|| uuid(source, 0)))
+        .ranks(ranks)
+        .rating(rating(source))
+        .stats(stats(source))
+        .history(&matches)
+        .build()
+}
@@ -1,11 +1,41 @@
+#[cfg(feature = "analysis")]
+pub mod analysis;
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(any(test, feature = "builders"))]
+pub mod builders;
+pub mod changes;
 pub mod client;
 pub mod error;
+#[cfg(feature = "export")]
+pub mod export;
+#[cfg(any(feature = "proptest", feature = "arbitrary"))]
+mod generate;
+#[cfg(feature = "test-utils")]
+pub mod mock;
+#[cfg(feature = "notify")]
+pub mod notify;
 #[cfg(feature = "player")]
 pub mod player;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "report")]
+pub mod report;
+pub mod scoreboard;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod store;
+pub mod sync;
 pub mod types;
+#[cfg(feature = "vcr")]
+pub mod vcr;
+#[cfg(feature = "watch")]
+pub mod watch;
 
 pub use client::{Client, ClientBuilder};
 pub use error::Error;
 #[cfg(feature = "player")]
-pub use player::Player;
+pub use player::{Player, TeamPlayers, Teammate};
 pub use types::{DataSource, Id, LeetifyId, Steam64Id, *};
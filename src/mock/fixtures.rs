@@ -0,0 +1,76 @@
+//! Deterministic sample data built with the [`builders`](crate::builders)
+//!
+//! [`profile`] and [`matches`] describe the same player, so the profile's
+//! recent matches and teammates agree with the match history.
+
+use crate::types::{MatchDetailsResponse, ProfileResponse};
+use chrono::{DateTime, Duration, Utc};
+
+/// The fixture player is the builders' default player
+pub use crate::builders::{LEETIFY_ID, NAME, STEAM64_ID};
+
+/// Map, data source and the rounds won and lost by the fixture player's
+/// team in each match, newest first
+const RESULTS: [(&str, &str, u32, u32); 3] = [
+    ("de_mirage", "matchmaking", 13, 9),
+    ("de_inferno", "faceit", 10, 13),
+    ("de_nuke", "matchmaking", 12, 12),
+];
+
+/// Finish time of the most recent fixture match
+fn latest() -> DateTime<Utc> {
+    DateTime::from_timestamp(1_735_732_800, 0).unwrap()
+}
+
+/// A ten player match in which the fixture player's team (2) won `won`
+/// rounds and lost `lost`
+pub fn match_details(
+    id: &str,
+    map_name: &str,
+    data_source: &str,
+    finished_at: DateTime<Utc>,
+    won: u32,
+    lost: u32,
+) -> MatchDetailsResponse {
+    MatchDetailsResponse::builder()
+        .id(id)
+        .map_name(map_name)
+        .data_source(data_source)
+        .data_source_match_id(format!("{}-{}", data_source, id))
+        .finished_at(finished_at)
+        .score(won, lost)
+        .build()
+}
+
+/// Match history of the fixture player, newest first: a win on Mirage, a
+/// loss on Inferno and a tie on Nuke, one day apart
+pub fn matches() -> Vec<MatchDetailsResponse> {
+    RESULTS
+        .iter()
+        .enumerate()
+        .map(|(i, &(map_name, data_source, won, lost))| {
+            match_details(
+                &format!("00000000-0000-4000-8000-00000000000{}", i + 1),
+                map_name,
+                data_source,
+                latest() - Duration::days(i as i64),
+                won,
+                lost,
+            )
+        })
+        .collect()
+}
+
+/// Profile of the fixture player, consistent with [`matches`]
+pub fn profile() -> ProfileResponse {
+    let matches = matches();
+    ProfileResponse::builder()
+        .steam64_id(STEAM64_ID)
+        .id(LEETIFY_ID.to_string())
+        .name(NAME)
+        .first_match_date(None)
+        .history(&matches)
+        .total_matches(matches.len() as u32)
+        .winrate(1.0 / 3.0)
+        .build()
+}
@@ -0,0 +1,479 @@
+//! In-process mock of the Leetify API for downstream tests
+//!
+//! [`MockServer`] serves `/v3/profile`, `/v3/profile/matches`,
+//! `/v2/matches/{game_id}`, `/v2/matches/{data_source}/{data_source_id}` and
+//! `/api-key/validate` from seeded data on a local port, so tests can point
+//! a [`Client`](crate::Client) at it with
+//! [`ClientBuilder::base_url`](crate::ClientBuilder::base_url). Failures and
+//! latency can be injected to exercise error handling.
+
+pub mod fixtures;
+
+use crate::client::API_KEY_HEADER;
+use crate::types::{MatchDetailsResponse, ProfileResponse};
+use axum::Router;
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{HeaderMap, Method, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// A failure the mock server answers with instead of the seeded data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockFailure {
+    /// 401, as for an invalid API key
+    Unauthorized,
+    /// 429, with a `Retry-After` header if a delay is given (in whole
+    /// seconds, rounded up)
+    RateLimited(Option<Duration>),
+    /// 500
+    ServerError,
+    /// 200 with a body that is not valid JSON
+    MalformedJson,
+}
+
+impl IntoResponse for MockFailure {
+    fn into_response(self) -> Response {
+        match self {
+            MockFailure::Unauthorized => error(StatusCode::UNAUTHORIZED, "Invalid API key"),
+            MockFailure::RateLimited(retry_after) => {
+                let mut response = error(StatusCode::TOO_MANY_REQUESTS, "Too many requests");
+                if let Some(retry_after) = retry_after {
+                    let seconds = retry_after.as_millis().div_ceil(1000) as u64;
+                    response
+                        .headers_mut()
+                        .insert(header::RETRY_AFTER, seconds.into());
+                }
+                response
+            }
+            MockFailure::ServerError => {
+                error(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+            }
+            MockFailure::MalformedJson => (
+                [(header::CONTENT_TYPE, "application/json")],
+                r#"{"name": "truncated", "ranks": {"#,
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// A request received by the mock server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    /// Raw query string, if any
+    pub query: Option<String>,
+    /// Value of the API key header, if sent
+    pub api_key: Option<String>,
+}
+
+#[derive(Default)]
+struct MockState {
+    profiles: Vec<ProfileResponse>,
+    matches: Vec<MatchDetailsResponse>,
+    api_key: Option<String>,
+    failure: Option<MockFailure>,
+    /// Number of requests the failure applies to; `None` for all
+    failure_count: Option<usize>,
+    latency: Duration,
+    requests: Vec<MockRequest>,
+}
+
+impl MockState {
+    /// Take the failure for the next request, if any
+    fn next_failure(&mut self) -> Option<MockFailure> {
+        let failure = self.failure?;
+        match &mut self.failure_count {
+            Some(0) => {
+                self.failure = None;
+                return None;
+            }
+            Some(count) => *count -= 1,
+            None => {}
+        }
+        Some(failure)
+    }
+
+    fn profile(&self, query: &HashMap<String, String>) -> Option<&ProfileResponse> {
+        self.profiles
+            .iter()
+            .find(|p| match query.get("steam64_id") {
+                Some(id) => &p.steam64_id == id,
+                None => query.get("id").is_some_and(|id| p.id.as_ref() == Some(id)),
+            })
+    }
+}
+
+type Shared = Arc<Mutex<MockState>>;
+
+fn lock(state: &Shared) -> MutexGuard<'_, MockState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Mock Leetify API on a local port
+///
+/// The server runs until the `MockServer` is dropped. Profiles and matches
+/// can be added at any time; match histories are built from the seeded
+/// matches the player took part in, newest first.
+///
+/// # Examples
+///
+/// ```no_run
+/// use leetify::Client;
+/// use leetify::mock::{MockFailure, MockServer, fixtures};
+///
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let mock = MockServer::seeded().await;
+/// let client = Client::builder().base_url(mock.url()).build()?;
+///
+/// let profile = client.get_profile(fixtures::STEAM64_ID).await?;
+/// assert_eq!(profile.name, fixtures::NAME);
+///
+/// mock.fail_times(MockFailure::ServerError, 1);
+/// assert!(matches!(
+///     client.get_profile(fixtures::STEAM64_ID).await,
+///     Err(leetify::Error::ServerError)
+/// ));
+/// # Ok(())
+/// # }
+/// ```
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Shared,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Start an empty mock server on a random local port
+    ///
+    /// # Panics
+    ///
+    /// Panics if no local port can be bound.
+    pub async fn start() -> Self {
+        let state = Shared::default();
+        let router = Router::new()
+            .route("/v3/profile", get(profile))
+            .route("/v3/profile/matches", get(profile_matches))
+            .route("/v2/matches/{game_id}", get(match_by_game_id))
+            .route(
+                "/v2/matches/{data_source}/{data_source_id}",
+                get(match_by_data_source),
+            )
+            .route("/api-key/validate", get(validate))
+            .layer(middleware::from_fn_with_state(state.clone(), intercept))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind the mock server");
+        let addr = listener.local_addr().expect("mock server has no address");
+        let task = tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+
+        Self { addr, state, task }
+    }
+
+    /// Start a mock server seeded with [`fixtures::profile`] and
+    /// [`fixtures::matches`]
+    pub async fn seeded() -> Self {
+        let server = Self::start().await;
+        server.add_profile(fixtures::profile());
+        server.add_matches(fixtures::matches());
+        server
+    }
+
+    /// Base URL to pass to [`ClientBuilder::base_url`](crate::ClientBuilder::base_url)
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Serve a profile, replacing one with the same Steam64 ID
+    pub fn add_profile(&self, profile: ProfileResponse) {
+        let mut state = lock(&self.state);
+        state
+            .profiles
+            .retain(|p| p.steam64_id != profile.steam64_id);
+        state.profiles.push(profile);
+    }
+
+    /// Serve a match, replacing one with the same ID
+    pub fn add_match(&self, details: MatchDetailsResponse) {
+        let mut state = lock(&self.state);
+        state.matches.retain(|m| m.id != details.id);
+        state.matches.push(details);
+    }
+
+    /// Serve several matches
+    pub fn add_matches(&self, matches: impl IntoIterator<Item = MatchDetailsResponse>) {
+        for details in matches {
+            self.add_match(details);
+        }
+    }
+
+    /// Answer every request without this API key with 401
+    pub fn require_api_key(&self, key: impl Into<String>) {
+        lock(&self.state).api_key = Some(key.into());
+    }
+
+    /// Answer all requests with `failure` until [`clear_failure`](Self::clear_failure)
+    pub fn fail(&self, failure: MockFailure) {
+        let mut state = lock(&self.state);
+        state.failure = Some(failure);
+        state.failure_count = None;
+    }
+
+    /// Answer the next `count` requests with `failure`
+    pub fn fail_times(&self, failure: MockFailure, count: usize) {
+        let mut state = lock(&self.state);
+        state.failure = Some(failure);
+        state.failure_count = Some(count);
+    }
+
+    /// Serve the seeded data again
+    pub fn clear_failure(&self) {
+        let mut state = lock(&self.state);
+        state.failure = None;
+        state.failure_count = None;
+    }
+
+    /// Delay every response
+    pub fn latency(&self, latency: Duration) {
+        lock(&self.state).latency = latency;
+    }
+
+    /// Requests received so far, oldest first
+    pub fn requests(&self) -> Vec<MockRequest> {
+        lock(&self.state).requests.clone()
+    }
+
+    /// Number of requests received so far
+    pub fn request_count(&self) -> usize {
+        lock(&self.state).requests.len()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, axum::Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+fn not_found() -> Response {
+    error(StatusCode::NOT_FOUND, "Not found")
+}
+
+/// Record the request, then apply latency, the API key check and injected
+/// failures before the route handler runs
+async fn intercept(
+    State(state): State<Shared>,
+    method: Method,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Response {
+    let api_key = headers
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let (latency, rejected) = {
+        let mut state = lock(&state);
+        state.requests.push(MockRequest {
+            method: method.to_string(),
+            path: request.uri().path().to_string(),
+            query: request.uri().query().map(str::to_string),
+            api_key: api_key.clone(),
+        });
+        let unauthorized = state
+            .api_key
+            .as_ref()
+            .is_some_and(|key| api_key.as_ref() != Some(key));
+        let rejected = if unauthorized {
+            Some(MockFailure::Unauthorized)
+        } else {
+            state.next_failure()
+        };
+        (state.latency, rejected)
+    };
+
+    if !latency.is_zero() {
+        tokio::time::sleep(latency).await;
+    }
+    match rejected {
+        Some(failure) => failure.into_response(),
+        None => next.run(request).await,
+    }
+}
+
+async fn profile(
+    State(state): State<Shared>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    match lock(&state).profile(&query) {
+        Some(profile) => axum::Json(profile).into_response(),
+        None => not_found(),
+    }
+}
+
+async fn profile_matches(
+    State(state): State<Shared>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    let state = lock(&state);
+    let steam64_id = match query.get("steam64_id") {
+        Some(id) => id.clone(),
+        None => match state.profile(&query) {
+            Some(profile) => profile.steam64_id.clone(),
+            None => return not_found(),
+        },
+    };
+
+    let mut matches: Vec<_> = state
+        .matches
+        .iter()
+        .filter(|m| m.player_stats(&steam64_id).is_some())
+        .collect();
+    matches.sort_by_key(|m| std::cmp::Reverse(m.finished_at));
+    axum::Json(matches).into_response()
+}
+
+async fn match_by_game_id(State(state): State<Shared>, Path(game_id): Path<String>) -> Response {
+    match lock(&state).matches.iter().find(|m| m.id == game_id) {
+        Some(details) => axum::Json(details).into_response(),
+        None => not_found(),
+    }
+}
+
+async fn match_by_data_source(
+    State(state): State<Shared>,
+    Path((data_source, data_source_id)): Path<(String, String)>,
+) -> Response {
+    let state = lock(&state);
+    let details = state
+        .matches
+        .iter()
+        .find(|m| m.data_source == data_source && m.data_source_match_id == data_source_id);
+    match details {
+        Some(details) => axum::Json(details).into_response(),
+        None => not_found(),
+    }
+}
+
+async fn validate(headers: HeaderMap) -> Response {
+    // A required key was already checked by `intercept`
+    if headers.contains_key(API_KEY_HEADER) {
+        StatusCode::OK.into_response()
+    } else {
+        MockFailure::Unauthorized.into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::error::Error;
+    use crate::types::{DataSource, Id};
+
+    async fn client(mock: &MockServer) -> Client {
+        Client::builder().base_url(mock.url()).build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_endpoints() {
+        let mock = MockServer::seeded().await;
+        let client = client(&mock).await;
+
+        let profile = client.get_profile(fixtures::STEAM64_ID).await.unwrap();
+        assert_eq!(profile.id.as_deref(), Some(fixtures::LEETIFY_ID));
+
+        let matches = client
+            .get_profile_matches(Id::Leetify(fixtures::LEETIFY_ID.into()))
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 3);
+        assert!(matches[0].finished_at > matches[1].finished_at);
+        assert_eq!(matches[0].id, profile.recent_matches[0].id);
+
+        let details = client
+            .get_match_by_game_id(matches[1].id.clone())
+            .await
+            .unwrap();
+        assert_eq!(details.map_name, "de_inferno");
+        let details = client
+            .get_match_by_data_source(DataSource::FACEIT, &details.data_source_match_id)
+            .await
+            .unwrap();
+        assert_eq!(details.id, matches[1].id);
+
+        assert!(matches!(
+            client.get_match_by_game_id("missing".to_string()).await,
+            Err(Error::Api(404, _))
+        ));
+        assert!(matches!(
+            client.validate_api_key().await,
+            Err(Error::InvalidApiKey)
+        ));
+        assert_eq!(mock.request_count(), 6);
+        assert_eq!(
+            mock.requests()[0].query.as_deref(),
+            Some("steam64_id=76561198283431555")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failures() {
+        let mock = MockServer::seeded().await;
+        let client = client(&mock).await;
+
+        mock.fail_times(MockFailure::RateLimited(Some(Duration::from_secs(7))), 1);
+        assert!(matches!(
+            client.get_profile(fixtures::STEAM64_ID).await,
+            Err(Error::RateLimited(Some(d))) if d == Duration::from_secs(7)
+        ));
+        assert!(client.get_profile(fixtures::STEAM64_ID).await.is_ok());
+
+        mock.fail_times(
+            MockFailure::RateLimited(Some(Duration::from_millis(200))),
+            1,
+        );
+        assert!(matches!(
+            client.get_profile(fixtures::STEAM64_ID).await,
+            Err(Error::RateLimited(Some(d))) if d == Duration::from_secs(1)
+        ));
+
+        mock.fail(MockFailure::MalformedJson);
+        assert!(client.get_profile(fixtures::STEAM64_ID).await.is_err());
+        assert!(client.get_profile(fixtures::STEAM64_ID).await.is_err());
+        mock.clear_failure();
+
+        mock.require_api_key("secret");
+        assert!(matches!(
+            client.get_profile(fixtures::STEAM64_ID).await,
+            Err(Error::InvalidApiKey)
+        ));
+        let keyed = Client::builder()
+            .base_url(mock.url())
+            .api_key("secret")
+            .build()
+            .unwrap();
+        keyed.validate_api_key().await.unwrap();
+
+        mock.latency(Duration::from_millis(50));
+        let start = std::time::Instant::now();
+        keyed.validate_api_key().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+}
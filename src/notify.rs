@@ -0,0 +1,561 @@
+//! Webhook notifications for newly finished matches of watched players
+//!
+//! A [`Notifier`] turns [`WatchedMatch`]es into webhook posts, either as
+//! Discord embeds or as plain JSON for custom consumers, and retries
+//! deliveries that fail because of rate limits, server errors or the
+//! network.
+
+use crate::analysis::Outcome;
+use crate::error::Error;
+use crate::types::MatchDetailsResponse;
+use crate::watch::WatchedMatch;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// Default number of retries after a failed delivery
+const DEFAULT_RETRIES: u32 = 3;
+
+/// Default delay before the first retry; doubled for every further retry
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Default upper bound for the delay before a retry
+const DEFAULT_MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Embed colors for wins, losses and ties
+const GREEN: u32 = 0x2e_cc_71;
+const RED: u32 = 0xe7_4c_3c;
+const YELLOW: u32 = 0xf1_c4_0f;
+
+/// Payload format of a webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WebhookFormat {
+    /// Discord-compatible message with one embed
+    Discord,
+    /// The [`MatchSummary`] as a JSON object
+    Json,
+}
+
+impl WebhookFormat {
+    /// Build the request body for a summary
+    pub fn payload(self, summary: &MatchSummary) -> Value {
+        match self {
+            WebhookFormat::Discord => discord_payload(summary),
+            WebhookFormat::Json => json!({
+                "event": "match_finished",
+                "match": summary,
+            }),
+        }
+    }
+}
+
+/// A finished match from a single player's perspective
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchSummary {
+    pub steam64_id: String,
+    pub player_name: String,
+    pub match_id: String,
+    pub map_name: String,
+    pub data_source: String,
+    pub finished_at: DateTime<Utc>,
+    pub outcome: Outcome,
+    /// Rounds won by the player's team
+    pub team_score: u32,
+    /// Rounds won by the opposing team
+    pub enemy_score: u32,
+    pub leetify_rating: Option<f64>,
+    pub kills: u32,
+    pub assists: u32,
+    pub deaths: u32,
+    /// Average damage per round
+    pub adr: f64,
+    /// Share of kills that were headshots, in percent
+    pub hs_percent: f64,
+    pub mvps: u32,
+    pub multi3k: u32,
+    pub multi4k: u32,
+    pub multi5k: u32,
+    /// Whether the player had the most kills in the match
+    pub top_fragger: bool,
+}
+
+impl MatchSummary {
+    /// Summarize a match for a participant, or `None` if the player has no
+    /// stats line in it
+    pub fn new(details: &MatchDetailsResponse, steam64_id: &str) -> Option<Self> {
+        let stats = details.player_stats(steam64_id)?;
+        let [a, b] = &details.team_scores;
+        let (team, enemy) = if stats.initial_team_number == b.team_number {
+            (b.score, a.score)
+        } else {
+            (a.score, b.score)
+        };
+        let hs_percent = if stats.total_kills == 0 {
+            0.0
+        } else {
+            stats.total_hs_kills as f64 / stats.total_kills as f64 * 100.0
+        };
+        let most_kills = details.stats.iter().map(|s| s.total_kills).max();
+
+        Some(Self {
+            steam64_id: stats.steam64_id.clone(),
+            player_name: stats.name.clone(),
+            match_id: details.id.clone(),
+            map_name: details.map_name.clone(),
+            data_source: details.data_source.clone(),
+            finished_at: details.finished_at,
+            outcome: Outcome::from_rounds(team, enemy),
+            team_score: team,
+            enemy_score: enemy,
+            leetify_rating: stats.leetify_rating,
+            kills: stats.total_kills,
+            assists: stats.total_assists,
+            deaths: stats.total_deaths,
+            adr: stats.dpr,
+            hs_percent,
+            mvps: stats.mvps,
+            multi3k: stats.multi3k,
+            multi4k: stats.multi4k,
+            multi5k: stats.multi5k,
+            top_fragger: stats.total_kills > 0 && most_kills == Some(stats.total_kills),
+        })
+    }
+
+    /// Summarize a watched match for the watched player
+    ///
+    /// Returns `None` if the match has no stats line for the player.
+    pub fn from_watched(watched: &WatchedMatch) -> Option<Self> {
+        Self::new(&watched.details, watched.steam64_id.as_ref())
+    }
+
+    /// Link to the match on leetify.com
+    pub fn url(&self) -> String {
+        format!("https://leetify.com/app/match-details/{}", self.match_id)
+    }
+}
+
+fn discord_payload(summary: &MatchSummary) -> Value {
+    let (result, color) = match summary.outcome {
+        Outcome::Win => ("Victory", GREEN),
+        Outcome::Loss => ("Defeat", RED),
+        Outcome::Tie => ("Tie", YELLOW),
+    };
+    let field = |name: &str, value: String| json!({ "name": name, "value": value, "inline": true });
+
+    let mut fields = vec![
+        field(
+            "Rating",
+            summary
+                .leetify_rating
+                .map_or_else(|| "-".to_string(), |r| format!("{:+.2}", r)),
+        ),
+        field(
+            "K / A / D",
+            format!(
+                "{} / {} / {}",
+                summary.kills, summary.assists, summary.deaths
+            ),
+        ),
+        field("ADR", format!("{:.0}", summary.adr)),
+        field("HS%", format!("{:.0}%", summary.hs_percent)),
+        field("MVPs", summary.mvps.to_string()),
+    ];
+    if summary.multi3k + summary.multi4k + summary.multi5k > 0 {
+        fields.push(field(
+            "3K / 4K / 5K",
+            format!(
+                "{} / {} / {}",
+                summary.multi3k, summary.multi4k, summary.multi5k
+            ),
+        ));
+    }
+
+    let mut description = format!(
+        "**{}** {} : {} ({})",
+        summary.player_name, summary.team_score, summary.enemy_score, summary.data_source
+    );
+    if summary.top_fragger {
+        description.push_str("\nTop fragger");
+    }
+
+    json!({
+        "embeds": [{
+            "title": format!("{} on {}", result, summary.map_name),
+            "description": description,
+            "url": summary.url(),
+            "color": color,
+            "timestamp": summary.finished_at.to_rfc3339(),
+            "fields": fields,
+            "footer": { "text": "Leetify" },
+        }],
+    })
+}
+
+/// A webhook endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Webhook {
+    pub url: String,
+    pub format: WebhookFormat,
+}
+
+/// Posts match summaries to webhooks
+///
+/// Deliveries are retried on 429 (after the `Retry-After` delay when one is
+/// sent), on 5xx responses and on network errors, with exponential backoff.
+/// The backoff is capped at [`max_retry_delay`](Self::max_retry_delay), and a
+/// webhook asking for a longer `Retry-After` delay fails the delivery instead
+/// of stalling it.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::{Client, Id};
+/// use futures_util::StreamExt;
+/// use leetify::notify::{Notifier, WebhookFormat};
+/// use std::time::Duration;
+///
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let client = Client::new();
+/// let notifier = Notifier::new()
+///     .webhook("https://discord.com/api/webhooks/...", WebhookFormat::Discord)
+///     .webhook("https://bot.internal/leetify", WebhookFormat::Json);
+///
+/// let ids = ["76561198283431555", "76561198000000000"];
+/// let mut matches = Box::pin(client.watch_players(ids, Duration::from_secs(60)));
+/// while let Some(new_match) = matches.next().await {
+///     notifier.notify(&new_match?).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    http: reqwest::Client,
+    webhooks: Vec<Webhook>,
+    retries: u32,
+    retry_delay: Duration,
+    max_retry_delay: Duration,
+}
+
+impl Notifier {
+    /// Notifier without webhooks, retrying failed deliveries 3 times
+    pub fn new() -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            webhooks: Vec::new(),
+            retries: DEFAULT_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            max_retry_delay: DEFAULT_MAX_RETRY_DELAY,
+        }
+    }
+
+    /// Post to a webhook (can be called several times)
+    pub fn webhook(mut self, url: impl Into<String>, format: WebhookFormat) -> Self {
+        self.webhooks.push(Webhook {
+            url: url.into(),
+            format,
+        });
+        self
+    }
+
+    /// Number of retries after a failed delivery
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Delay before the first retry; doubled for every further retry
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Longest delay before a retry (60 seconds by default)
+    ///
+    /// Longer backoffs are shortened to it. A `Retry-After` delay above it
+    /// makes the delivery fail with [`Error::Webhook`] right away.
+    pub fn max_retry_delay(mut self, delay: Duration) -> Self {
+        self.max_retry_delay = delay;
+        self
+    }
+
+    /// Use a custom HTTP client (e.g. with a proxy or timeout)
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http = client;
+        self
+    }
+
+    /// The configured webhooks
+    pub fn webhooks(&self) -> &[Webhook] {
+        &self.webhooks
+    }
+
+    /// Post a watched match to all webhooks
+    ///
+    /// Returns `false` without posting if the match has no stats line for
+    /// the watched player (see [`MatchSummary::from_watched`]).
+    pub async fn notify(&self, watched: &WatchedMatch) -> Result<bool, Error> {
+        match MatchSummary::from_watched(watched) {
+            Some(summary) => self.send(&summary).await.map(|()| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Post a summary to all webhooks
+    ///
+    /// Every webhook is attempted; the first failure is returned.
+    pub async fn send(&self, summary: &MatchSummary) -> Result<(), Error> {
+        let mut result = Ok(());
+        for webhook in &self.webhooks {
+            let delivery = self
+                .deliver(webhook, &webhook.format.payload(summary))
+                .await;
+            if result.is_ok() {
+                result = delivery;
+            }
+        }
+        result
+    }
+
+    /// Post a payload to a webhook, retrying transient failures
+    pub async fn deliver(&self, webhook: &Webhook, payload: &Value) -> Result<(), Error> {
+        let mut attempt = 0;
+        loop {
+            let (error, retry_after) = match self.http.post(&webhook.url).json(payload).send().await
+            {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => {
+                    let status = response.status();
+                    let retry_after = crate::client::retry_after(&response);
+                    let body = response.text().await.unwrap_or_default();
+                    let retry_after = retry_after.or_else(|| discord_retry_after(&body));
+                    let retryable = status.as_u16() == 429 || status.is_server_error();
+                    let error = Error::Webhook(status.as_u16(), body);
+                    if !retryable {
+                        return Err(error);
+                    }
+                    (error, retry_after)
+                }
+                Err(e) => (Error::Http(e), None),
+            };
+
+            if attempt >= self.retries {
+                return Err(error);
+            }
+            let delay = match retry_after {
+                Some(delay) if delay > self.max_retry_delay => return Err(error),
+                Some(delay) => delay,
+                None => self
+                    .retry_delay
+                    .saturating_mul(2u32.saturating_pow(attempt))
+                    .min(self.max_retry_delay),
+            };
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+impl Default for Notifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read the `retry_after` field (in seconds) Discord sends with a 429
+fn discord_retry_after(body: &str) -> Option<Duration> {
+    let value: Value = serde_json::from_str(body).ok()?;
+    Duration::try_from_secs_f64(value.get("retry_after")?.as_f64()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Id, PlayerStats, TeamScore};
+    use crate::watch::WatchState;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn game() -> MatchDetailsResponse {
+        let player = |id: &str, team: u32, kills: u32| PlayerStats {
+            steam64_id: id.to_string(),
+            name: id.to_string(),
+            initial_team_number: team,
+            total_kills: kills,
+            total_hs_kills: kills / 2,
+            total_deaths: 15,
+            dpr: 91.6,
+            multi3k: 1,
+            leetify_rating: Some(0.05),
+            ..Default::default()
+        };
+        MatchDetailsResponse {
+            id: "m1".to_string(),
+            map_name: "de_mirage".to_string(),
+            data_source: "matchmaking".to_string(),
+            team_scores: [
+                TeamScore {
+                    team_number: 2,
+                    score: 13,
+                },
+                TeamScore {
+                    team_number: 3,
+                    score: 9,
+                },
+            ],
+            stats: vec![player("winner", 2, 20), player("loser", 3, 24)],
+            ..Default::default()
+        }
+    }
+
+    /// Local webhook stand-in answering with the given statuses and
+    /// `Retry-After` delays in turn and recording the request bodies
+    async fn webhook(responses: Vec<(u16, Option<u64>)>) -> (String, Arc<Mutex<Vec<Value>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let recorded = bodies.clone();
+
+        tokio::spawn(async move {
+            for (status, retry_after) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 4096];
+                let body = loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length: usize = head
+                            .lines()
+                            .find_map(|l| {
+                                l.to_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if body.len() >= length {
+                            break body.to_string();
+                        }
+                    }
+                };
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push(serde_json::from_str(&body).unwrap());
+                let retry_after = retry_after
+                    .map(|seconds| format!("retry-after: {}\r\n", seconds))
+                    .unwrap_or_default();
+                let response = format!(
+                    "HTTP/1.1 {} Status\r\n{}content-length: 0\r\nconnection: close\r\n\r\n",
+                    status, retry_after
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (url, bodies)
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = MatchSummary::new(&game(), "loser").unwrap();
+        assert_eq!(summary.outcome, Outcome::Loss);
+        assert_eq!((summary.team_score, summary.enemy_score), (9, 13));
+        assert_eq!(summary.hs_percent, 50.0);
+        assert!(summary.top_fragger);
+
+        let summary = MatchSummary::new(&game(), "winner").unwrap();
+        assert_eq!(summary.outcome, Outcome::Win);
+        assert!(!summary.top_fragger);
+        assert!(MatchSummary::new(&game(), "missing").is_none());
+    }
+
+    #[test]
+    fn test_payloads() {
+        let summary = MatchSummary::new(&game(), "winner").unwrap();
+
+        let discord = WebhookFormat::Discord.payload(&summary);
+        let embed = &discord["embeds"][0];
+        assert_eq!(embed["title"], "Victory on de_mirage");
+        assert_eq!(embed["description"], "**winner** 13 : 9 (matchmaking)");
+        assert_eq!(embed["color"], GREEN);
+        assert_eq!(embed["url"], "https://leetify.com/app/match-details/m1");
+        assert_eq!(embed["fields"][0]["value"], "+0.05");
+        assert_eq!(embed["fields"][1]["value"], "20 / 0 / 15");
+        assert_eq!(embed["fields"][5]["value"], "1 / 0 / 0");
+
+        let plain = WebhookFormat::Json.payload(&summary);
+        assert_eq!(plain["event"], "match_finished");
+        assert_eq!(plain["match"]["outcome"], "Win");
+        assert_eq!(plain["match"]["map_name"], "de_mirage");
+    }
+
+    #[tokio::test]
+    async fn test_delivery_retries() {
+        let (url, bodies) = webhook(vec![(500, None), (429, None), (204, None)]).await;
+        let notifier = Notifier::new()
+            .webhook(url, WebhookFormat::Json)
+            .retry_delay(Duration::from_millis(1));
+        let watched = WatchedMatch {
+            player: Id::Leetify("5ea07280-2399-4c7e-88ab-f2f7db0c449f".into()),
+            steam64_id: "winner".into(),
+            details: game(),
+            state: WatchState::default(),
+        };
+
+        assert!(notifier.notify(&watched).await.unwrap());
+        let bodies = bodies.lock().unwrap();
+        assert_eq!(bodies.len(), 3);
+        assert_eq!(bodies[2]["match"]["match_id"], "m1");
+    }
+
+    #[tokio::test]
+    async fn test_delivery_failures() {
+        let (url, bodies) = webhook(vec![(400, None)]).await;
+        let notifier = Notifier::new().webhook(url, WebhookFormat::Discord);
+        let summary = MatchSummary::new(&game(), "winner").unwrap();
+        assert!(matches!(
+            notifier.send(&summary).await,
+            Err(Error::Webhook(400, _))
+        ));
+        assert_eq!(bodies.lock().unwrap().len(), 1);
+
+        let (url, _) = webhook(vec![(503, None), (503, None)]).await;
+        let notifier = Notifier::new()
+            .webhook(url, WebhookFormat::Discord)
+            .retries(1)
+            .retry_delay(Duration::from_millis(1));
+        assert!(matches!(
+            notifier.send(&summary).await,
+            Err(Error::Webhook(503, _))
+        ));
+
+        let watched = WatchedMatch {
+            player: Id::Steam64("spectator".into()),
+            steam64_id: "spectator".into(),
+            details: game(),
+            state: WatchState::default(),
+        };
+        assert!(!notifier.notify(&watched).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_delivery_retry_after_limit() {
+        let summary = MatchSummary::new(&game(), "winner").unwrap();
+
+        let (url, bodies) = webhook(vec![(429, Some(3600))]).await;
+        let notifier = Notifier::new().webhook(url, WebhookFormat::Json);
+        let delivery = tokio::time::timeout(Duration::from_secs(5), notifier.send(&summary));
+        assert!(matches!(delivery.await, Ok(Err(Error::Webhook(429, _)))));
+        assert_eq!(bodies.lock().unwrap().len(), 1);
+
+        let (url, bodies) = webhook(vec![(503, None), (204, None)]).await;
+        let notifier = Notifier::new()
+            .webhook(url, WebhookFormat::Json)
+            .retry_delay(Duration::from_secs(3600))
+            .max_retry_delay(Duration::from_millis(1));
+        notifier.send(&summary).await.unwrap();
+        assert_eq!(bodies.lock().unwrap().len(), 2);
+    }
+}
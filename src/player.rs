@@ -1,12 +1,21 @@
 use crate::client::Client;
 use crate::error::Error;
-use crate::types::{Id, MatchDetailsResponse, ProfileResponse};
+use crate::types::{
+    Id, LeetifyId, MatchDetailsResponse, PlayerStats, ProfileResponse, Ranks, RecentTeammate,
+    Steam64Id,
+};
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
 /// High-level API for interacting with a specific player
 ///
 /// This struct provides a convenient way to work with player data
 /// without needing to pass the player id to each method call.
 ///
+/// A `Player` owns a (cheaply cloned) handle to its `Client`, so it can be
+/// stored in long-lived structs or moved into spawned tasks.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -25,17 +34,65 @@ use crate::types::{Id, MatchDetailsResponse, ProfileResponse};
 /// # Ok(())
 /// # }
 /// ```
-pub struct Player<'a> {
+///
+/// Moving a player into a background task:
+///
+/// ```no_run
+/// # use leetify::{Client, Id};
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let client = Client::new();
+/// let player = client.player(Id::Steam64("76561198283431555".into()));
+///
+/// let handle = tokio::spawn(async move { player.matches().await });
+/// let matches = handle.await.unwrap()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Clones of a `Player` share the same cache.
+#[derive(Clone)]
+pub struct Player {
     id: Id,
-    client: &'a Client,
+    client: Client,
+    cache: Arc<Mutex<PlayerCache>>,
+    max_age: Option<Duration>,
 }
 
-impl<'a> Player<'a> {
+/// Last fetched responses of a player
+#[derive(Default)]
+struct PlayerCache {
+    profile: Option<Cached<ProfileResponse>>,
+    matches: Option<Cached<Vec<MatchDetailsResponse>>>,
+}
+
+struct Cached<T> {
+    value: T,
+    fetched_at: DateTime<Utc>,
+    fetched: Instant,
+}
+
+impl<T: Clone> Cached<T> {
+    fn new(value: T) -> Self {
+        Self {
+            value,
+            fetched_at: Utc::now(),
+            fetched: Instant::now(),
+        }
+    }
+
+    /// Get the value if it is younger than `max_age`
+    fn fresh(&self, max_age: Option<Duration>) -> Option<T> {
+        let max_age = max_age?;
+        (self.fetched.elapsed() < max_age).then(|| self.value.clone())
+    }
+}
+
+impl Player {
     /// Create a new Player instance
     ///
     /// # Arguments
     /// * `id` - The player id (Steam64 ID or Leetify ID)
-    /// * `client` - Reference to the Leetify client
+    /// * `client` - The Leetify client (cloned, which only bumps a reference count)
     ///
     /// # Examples
     ///
@@ -44,18 +101,52 @@ impl<'a> Player<'a> {
     /// let client = Client::new();
     /// let player = Player::new(Id::Steam64("76561198283431555".into()), &client);
     /// ```
-    pub fn new(id: impl Into<Id>, client: &'a Client) -> Self {
+    pub fn new(id: impl Into<Id>, client: &Client) -> Self {
         Self {
             id: id.into(),
-            client,
+            client: client.clone(),
+            cache: Arc::default(),
+            max_age: None,
         }
     }
 
+    /// Serve `profile()` and `matches()` from memory for up to `max_age`
+    ///
+    /// Without a freshness window every call hits the network, although the
+    /// last response is still kept for [`cached_profile`](Self::cached_profile)
+    /// and the convenience accessors.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Player, Id};
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let player = client
+    ///     .player(Id::Steam64("76561198283431555".into()))
+    ///     .with_cache(Duration::from_secs(300));
+    ///
+    /// let profile = player.profile().await?; // fetched
+    /// let profile = player.profile().await?; // served from memory
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_cache(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
     /// Get the player's id
     pub fn id(&self) -> &Id {
         &self.id
     }
 
+    /// Get the client used by this player
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
     /// Get the player's profile
     ///
     /// # Examples
@@ -71,7 +162,15 @@ impl<'a> Player<'a> {
     /// # }
     /// ```
     pub async fn profile(&self) -> Result<ProfileResponse, Error> {
-        self.client.get_profile(self.id.clone()).await
+        let cached = self
+            .cache()
+            .profile
+            .as_ref()
+            .and_then(|p| p.fresh(self.max_age));
+        if let Some(profile) = cached {
+            return Ok(profile);
+        }
+        self.refresh().await
     }
 
     /// Get the player's match history
@@ -89,7 +188,364 @@ impl<'a> Player<'a> {
     /// # }
     /// ```
     pub async fn matches(&self) -> Result<Vec<MatchDetailsResponse>, Error> {
-        self.client.get_profile_matches(self.id.clone()).await
+        let cached = self
+            .cache()
+            .matches
+            .as_ref()
+            .and_then(|m| m.fresh(self.max_age));
+        if let Some(matches) = cached {
+            return Ok(matches);
+        }
+
+        let matches = self.client.get_profile_matches(self.id.clone()).await?;
+        self.cache().matches = Some(Cached::new(matches.clone()));
+        Ok(matches)
+    }
+
+    /// Fetch the profile, bypassing the cache, and drop the cached matches
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Player, Id};
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let player = Player::new(Id::Steam64("76561198283431555".into()), &client);
+    /// let profile = player.refresh().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn refresh(&self) -> Result<ProfileResponse, Error> {
+        let profile = self.client.get_profile(self.id.clone()).await?;
+
+        let mut cache = self.cache();
+        cache.profile = Some(Cached::new(profile.clone()));
+        cache.matches = None;
+        Ok(profile)
+    }
+
+    /// Get the last fetched profile without making a request
+    pub fn cached_profile(&self) -> Option<ProfileResponse> {
+        self.cache().profile.as_ref().map(|p| p.value.clone())
+    }
+
+    /// Get when the profile was last fetched
+    pub fn last_fetched_at(&self) -> Option<DateTime<Utc>> {
+        self.cache().profile.as_ref().map(|p| p.fetched_at)
+    }
+
+    /// Get the player's name, fetching the profile on first use
+    pub async fn name(&self) -> Result<String, Error> {
+        Ok(self.known_profile().await?.name)
+    }
+
+    /// Get the player's Steam64 ID
+    ///
+    /// If the player was created from a Leetify ID, the missing ID is taken
+    /// from the client's id mapping or the cached profile, and the profile
+    /// is only fetched if neither knows it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Id};
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let player = client.player(Id::Leetify("5ea07280-2399-4c7e-88ab-f2f7db0c449f".into()));
+    /// let steam64_id = player.steam64_id().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn steam64_id(&self) -> Result<Steam64Id, Error> {
+        match &self.id {
+            Id::Steam64(id) => Ok(id.clone()),
+            Id::Leetify(id) => match self.client.known_steam64_id(id) {
+                Some(steam64_id) => Ok(steam64_id),
+                None => Ok(self.known_profile().await?.steam64_id.into()),
+            },
+        }
+    }
+
+    /// Get the player's Leetify ID
+    ///
+    /// If the player was created from a Steam64 ID, the missing ID is taken
+    /// from the client's id mapping or the cached profile, and the profile
+    /// is only fetched if neither knows it. Returns `None` if the player has
+    /// no Leetify account.
+    pub async fn leetify_id(&self) -> Result<Option<LeetifyId>, Error> {
+        match &self.id {
+            Id::Leetify(id) => Ok(Some(id.clone())),
+            Id::Steam64(id) => match self.client.known_leetify_id(id) {
+                Some(leetify_id) => Ok(Some(leetify_id)),
+                None => Ok(self.known_profile().await?.id.map(LeetifyId::from)),
+            },
+        }
+    }
+
+    /// Get the player's ranks, fetching the profile on first use
+    pub async fn ranks(&self) -> Result<Ranks, Error> {
+        Ok(self.known_profile().await?.ranks)
+    }
+
+    /// Get the player's recent teammates as `Player`s
+    ///
+    /// Uses the cached profile if available, otherwise fetches it. The
+    /// returned players share this player's client.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Id};
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let player = client.player(Id::Steam64("76561198283431555".into()));
+    ///
+    /// for teammate in player.teammates().await? {
+    ///     let profile = teammate.player.profile().await?;
+    ///     println!("{} ({} recent matches together)", profile.name, teammate.recent_matches_count);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn teammates(&self) -> Result<Vec<Teammate>, Error> {
+        let profile = self.known_profile().await?;
+        Ok(profile
+            .recent_teammates
+            .iter()
+            .map(|teammate| Teammate {
+                player: teammate.player(&self.client),
+                recent_matches_count: teammate.recent_matches_count,
+            })
+            .collect())
+    }
+
+    /// Get the cached profile if it may be used, otherwise fetch it
+    ///
+    /// Without a freshness window any cached profile is used.
+    async fn known_profile(&self) -> Result<ProfileResponse, Error> {
+        if self.max_age.is_none()
+            && let Some(profile) = self.cached_profile()
+        {
+            return Ok(profile);
+        }
+        self.profile().await
+    }
+
+    fn cache(&self) -> MutexGuard<'_, PlayerCache> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Get the player's per-map performance breakdown
+    ///
+    /// Fetches the profile and match history and combines them into a
+    /// [`MapReport`](crate::analysis::MapReport).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Player, Id};
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let player = Player::new(Id::Steam64("76561198283431555".into()), &client);
+    /// let report = player.map_report().await?;
+    /// if let Some(nuke) = report.get("de_nuke") {
+    ///     println!("Nuke win rate: {:.0}%", nuke.win_rate * 100.0);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub async fn map_report(&self) -> Result<crate::analysis::MapReport, Error> {
+        let (profile, matches) = tokio::try_join!(self.profile(), self.matches())?;
+        Ok(crate::analysis::MapReport::new(
+            &profile.steam64_id,
+            &matches,
+            &profile.ranks,
+        ))
+    }
+
+    /// Get the player's recent form as a time series
+    ///
+    /// Built from the recent matches included in the profile, so this only
+    /// needs a single request.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Player, Id};
+    /// # use leetify::analysis::Metric;
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let player = Player::new(Id::Steam64("76561198283431555".into()), &client);
+    /// let form = player.form().await?;
+    /// if let Some(streak) = form.current_streak() {
+    ///     println!("On a {}-match {:?} streak", streak.length, streak.outcome);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub async fn form(&self) -> Result<crate::analysis::Form, Error> {
+        let profile = self.profile().await?;
+        Ok(crate::analysis::Form::from_recent_matches(
+            &profile.recent_matches,
+        ))
+    }
+
+    /// Get the player's teammate synergy analysis
+    ///
+    /// Joins the profile's recent teammates with the match history.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Player, Id};
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let player = Player::new(Id::Steam64("76561198283431555".into()), &client);
+    /// let synergy = player.synergy().await?;
+    /// for partner in synergy.best_partners(3) {
+    ///     println!("{}: {:.0}% win rate together", partner.steam64_id, partner.win_rate_with * 100.0);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub async fn synergy(&self) -> Result<crate::analysis::SynergyReport, Error> {
+        let (profile, matches) = tokio::try_join!(self.profile(), self.matches())?;
+        Ok(crate::analysis::SynergyReport::new(
+            &profile.steam64_id,
+            &profile.recent_teammates,
+            &matches,
+        ))
+    }
+
+    /// Scan the player's match history for banned participants
+    ///
+    /// Profiles are fetched for the participants of every match Leetify
+    /// flags with `has_banned_player`, once per participant.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Player, Id};
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let player = Player::new(Id::Steam64("76561198283431555".into()), &client);
+    /// let report = player.ban_report().await?;
+    /// println!("{} matches with cheaters detected", report.cheater_detected().count());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub async fn ban_report(&self) -> Result<crate::analysis::BanReport, Error> {
+        let (steam64_id, matches) = tokio::try_join!(self.steam64_id(), self.matches())?;
+
+        let ids = matches
+            .iter()
+            .filter(|m| m.has_banned_player)
+            .flat_map(|m| &m.stats)
+            .map(|s| s.steam64_id.as_str())
+            .filter(|id| *id != steam64_id.as_ref());
+        let profiles = crate::analysis::fetch_profiles(&self.client, ids).await?;
+
+        Ok(crate::analysis::BanReport::new(
+            steam64_id.as_ref(),
+            &matches,
+            &profiles,
+        ))
+    }
+
+    /// Build a shareable report of the player
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Player, Id};
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let player = Player::new(Id::Steam64("76561198283431555".into()), &client);
+    /// let report = player.report().await?;
+    /// std::fs::write("report.md", report.to_markdown()?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "report")]
+    pub async fn report(&self) -> Result<crate::report::PlayerReport, Error> {
+        let (profile, matches) = tokio::try_join!(self.profile(), self.matches())?;
+        Ok(crate::report::PlayerReport::new(&profile, &matches))
+    }
+}
+
+/// A recent teammate of a player
+#[derive(Clone)]
+pub struct Teammate {
+    pub player: Player,
+    /// Number of recent matches played together
+    pub recent_matches_count: u32,
+}
+
+/// The participants of one team in a match
+#[derive(Clone)]
+pub struct TeamPlayers {
+    pub team_number: u32,
+    pub players: Vec<Player>,
+}
+
+impl RecentTeammate {
+    /// Create a `Player` for this teammate
+    pub fn player(&self, client: &Client) -> Player {
+        Player::new(Steam64Id::from(self.steam64_id.as_str()), client)
+    }
+}
+
+impl PlayerStats {
+    /// Create a `Player` for this participant
+    pub fn player(&self, client: &Client) -> Player {
+        Player::new(Steam64Id::from(self.steam64_id.as_str()), client)
+    }
+}
+
+impl MatchDetailsResponse {
+    /// Get every participant as a `Player`, split by team
+    ///
+    /// Teams are ordered as in `team_scores`; participants are grouped by
+    /// their initial team number.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::Client;
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let details = client.get_match_by_game_id("match-id-123".to_string()).await?;
+    ///
+    /// for team in details.players(&client) {
+    ///     println!("Team {}: {} players", team.team_number, team.players.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn players(&self, client: &Client) -> Vec<TeamPlayers> {
+        let mut team_numbers: Vec<u32> = self.team_scores.iter().map(|t| t.team_number).collect();
+        for stats in &self.stats {
+            if !team_numbers.contains(&stats.initial_team_number) {
+                team_numbers.push(stats.initial_team_number);
+            }
+        }
+
+        team_numbers
+            .into_iter()
+            .map(|team_number| TeamPlayers {
+                team_number,
+                players: self
+                    .stats
+                    .iter()
+                    .filter(|s| s.initial_team_number == team_number)
+                    .map(|s| s.player(client))
+                    .collect(),
+            })
+            .collect()
     }
 }
 
@@ -113,7 +569,68 @@ impl Client {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn player(&self, id: impl Into<Id>) -> crate::player::Player<'_> {
+    pub fn player(&self, id: impl Into<Id>) -> crate::player::Player {
         crate::player::Player::new(id, self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_freshness() {
+        let cached = Cached::new(1);
+        assert_eq!(cached.fresh(None), None);
+        assert_eq!(cached.fresh(Some(Duration::from_secs(60))), Some(1));
+        assert_eq!(cached.fresh(Some(Duration::ZERO)), None);
+    }
+
+    #[test]
+    fn test_match_players_split_by_team() {
+        use crate::types::TeamScore;
+
+        let stats = |id: &str, team: u32| PlayerStats {
+            steam64_id: id.to_string(),
+            initial_team_number: team,
+            ..Default::default()
+        };
+        let details = MatchDetailsResponse {
+            team_scores: [
+                TeamScore {
+                    team_number: 3,
+                    score: 13,
+                },
+                TeamScore {
+                    team_number: 2,
+                    score: 7,
+                },
+            ],
+            stats: vec![stats("1", 2), stats("2", 3), stats("3", 2)],
+            ..Default::default()
+        };
+
+        let teams = details.players(&Client::new());
+        assert_eq!(teams.len(), 2);
+        assert_eq!(teams[0].team_number, 3);
+        assert_eq!(teams[0].players.len(), 1);
+        assert_eq!(teams[1].players[1].id(), &Id::Steam64("3".into()));
+    }
+
+    #[test]
+    fn test_cache_shared_between_clones() {
+        let client = Client::new();
+        let player = client
+            .player(Id::Steam64("76561198283431555".into()))
+            .with_cache(Duration::from_secs(60));
+        let clone = player.clone();
+        assert!(player.cached_profile().is_none());
+
+        player.cache().profile = Some(Cached::new(ProfileResponse {
+            name: "cached".to_string(),
+            ..Default::default()
+        }));
+        assert_eq!(clone.cached_profile().unwrap().name, "cached");
+        assert!(clone.last_fetched_at().is_some());
+    }
+}
@@ -0,0 +1,108 @@
+//! [proptest](https://docs.rs/proptest) strategies for the response types
+//!
+//! Generated values are random but internally consistent: players' rounds
+//! match the team scores, `shots_hit_foe <= shots_fired`, multi-kills add up
+//! to the kills, FACEIT levels match the Elo, and a profile's recent matches
+//! are sorted and agree with their scores. Every response type also
+//! implements [`Arbitrary`], so `any::<MatchDetailsResponse>()` works too.
+//!
+//! # Examples
+//!
+//! ```
+//! use leetify::proptest::match_details;
+//! use proptest::prelude::*;
+//!
+//! proptest!(|(details in match_details())| {
+//!     let _ = leetify::scoreboard::Scoreboard::new(&details).to_string();
+//! });
+//! ```
+
+use crate::generate::{self, Source};
+use crate::types::{
+    CompetitiveRank, MatchDetailsResponse, PlatformBanInfo, PlayerStats, ProfileResponse, Ranks,
+    Rating, RecentMatch, RecentTeammate, Stats, TeamScore,
+};
+use ::proptest::arbitrary::Arbitrary;
+use ::proptest::prelude::*;
+
+/// Numbers drawn for one generated value; larger values reuse them from the
+/// start
+const DRAWS: usize = 1024;
+
+/// Numbers from proptest, so generated values shrink towards the lower
+/// bounds of every range
+struct Draws {
+    values: Vec<u32>,
+    next: usize,
+}
+
+impl Source for Draws {
+    fn int(&mut self, lo: u32, hi: u32) -> u32 {
+        let value = self.values[self.next % self.values.len()];
+        self.next += 1;
+        lo + value % (hi - lo + 1)
+    }
+}
+
+fn generated<T: std::fmt::Debug + 'static>(generate: fn(&mut Draws) -> T) -> BoxedStrategy<T> {
+    prop::collection::vec(any::<u32>(), DRAWS)
+        .prop_map(move |values| generate(&mut Draws { values, next: 0 }))
+        .boxed()
+}
+
+macro_rules! strategies {
+    ($($name:ident => $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Consistent [`", stringify!($ty), "`] values")]
+            pub fn $name() -> BoxedStrategy<$ty> {
+                generated(generate::$name)
+            }
+
+            impl Arbitrary for $ty {
+                type Parameters = ();
+                type Strategy = BoxedStrategy<$ty>;
+
+                fn arbitrary_with(_: ()) -> Self::Strategy {
+                    $name()
+                }
+            }
+        )*
+    };
+}
+
+strategies! {
+    profile => ProfileResponse,
+    ranks => Ranks,
+    competitive_rank => CompetitiveRank,
+    rating => Rating,
+    stats => Stats,
+    recent_match => RecentMatch,
+    recent_teammate => RecentTeammate,
+    platform_ban_info => PlatformBanInfo,
+    match_details => MatchDetailsResponse,
+    team_score => TeamScore,
+    player_stats => PlayerStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::checks;
+
+    proptest! {
+        #[test]
+        fn test_player_stats_are_consistent(stats in player_stats()) {
+            checks::player_stats(&stats);
+        }
+
+        #[test]
+        fn test_matches_are_consistent(details in any::<MatchDetailsResponse>()) {
+            checks::match_details(&details);
+        }
+
+        #[test]
+        fn test_profiles_are_consistent(profile in profile()) {
+            checks::profile(&profile);
+        }
+    }
+}
@@ -0,0 +1,429 @@
+//! Shareable HTML and Markdown reports for a player
+//!
+//! Reports are rendered with [MiniJinja](https://docs.rs/minijinja) templates.
+//! The built-in templates ([`HTML_TEMPLATE`], [`MARKDOWN_TEMPLATE`]) can be
+//! replaced with custom ones through [`PlayerReport::render_template`].
+
+mod svg;
+
+use crate::analysis::{Form, MapPerformance, MapReport, Metric, TimePoint, Trend};
+use crate::changes::PremierTier;
+use crate::error::Error;
+use crate::types::{
+    CompetitiveRank, MatchDetailsResponse, PlatformBanInfo, ProfileResponse, Stats,
+};
+use chrono::{DateTime, Utc};
+use minijinja::{Environment, Value, context};
+use serde::{Deserialize, Serialize};
+
+/// Built-in HTML template; self-contained, with inline CSS and SVG charts
+pub const HTML_TEMPLATE: &str = include_str!("templates/player.html");
+
+/// Built-in Markdown template
+pub const MARKDOWN_TEMPLATE: &str = include_str!("templates/player.md");
+
+/// Output format of a report template
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Values are HTML-escaped
+    Html,
+    /// Values are inserted as-is
+    Markdown,
+}
+
+impl ReportFormat {
+    /// Template name; its extension controls auto-escaping
+    fn template_name(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "player.html",
+            ReportFormat::Markdown => "player.md",
+        }
+    }
+}
+
+/// Reference values a `Stats` value is judged against
+#[derive(Debug, Clone, Copy)]
+pub struct Reference {
+    pub label: &'static str,
+    pub value: fn(&Stats) -> f64,
+    /// Values at least this good are strengths
+    pub strong: f64,
+    /// Values at least this bad are weaknesses; lower values are better when
+    /// this is above `strong`
+    pub weak: f64,
+}
+
+impl Reference {
+    fn verdict(&self, value: f64) -> Verdict {
+        let higher_is_better = self.strong >= self.weak;
+        let at_least = |a: f64, b: f64| if higher_is_better { a >= b } else { a <= b };
+        if at_least(value, self.strong) {
+            Verdict::Strength
+        } else if at_least(self.weak, value) {
+            Verdict::Weakness
+        } else {
+            Verdict::Average
+        }
+    }
+}
+
+/// Rough reference values for typical matchmaking and FACEIT players
+pub const DEFAULT_REFERENCES: &[Reference] = &[
+    Reference {
+        label: "Aim accuracy (enemy spotted)",
+        value: |s| s.accuracy_enemy_spotted,
+        strong: 45.0,
+        weak: 30.0,
+    },
+    Reference {
+        label: "Head accuracy",
+        value: |s| s.accuracy_head,
+        strong: 25.0,
+        weak: 15.0,
+    },
+    Reference {
+        label: "Spray accuracy",
+        value: |s| s.spray_accuracy,
+        strong: 45.0,
+        weak: 30.0,
+    },
+    Reference {
+        label: "Crosshair placement (degrees)",
+        value: |s| s.preaim,
+        strong: 7.0,
+        weak: 11.0,
+    },
+    Reference {
+        label: "Reaction time (ms)",
+        value: |s| s.reaction_time_ms,
+        strong: 550.0,
+        weak: 700.0,
+    },
+    Reference {
+        label: "Counter-strafing",
+        value: |s| s.counter_strafing_good_shots_ratio,
+        strong: 80.0,
+        weak: 65.0,
+    },
+    Reference {
+        label: "Enemies flashed per flashbang",
+        value: |s| s.flashbang_hit_foe_per_flashbang,
+        strong: 0.7,
+        weak: 0.4,
+    },
+    Reference {
+        label: "HE damage to enemies",
+        value: |s| s.he_foes_damage_avg,
+        strong: 10.0,
+        weak: 5.0,
+    },
+    Reference {
+        label: "Unused utility on death",
+        value: |s| s.utility_on_death_avg,
+        strong: 100.0,
+        weak: 250.0,
+    },
+    Reference {
+        label: "Trade kill success (%)",
+        value: |s| s.trade_kills_success_percentage,
+        strong: 50.0,
+        weak: 35.0,
+    },
+    Reference {
+        label: "CT opening duels won (%)",
+        value: |s| s.ct_opening_duel_success_percentage,
+        strong: 55.0,
+        weak: 45.0,
+    },
+    Reference {
+        label: "T opening duels won (%)",
+        value: |s| s.t_opening_duel_success_percentage,
+        strong: 55.0,
+        weak: 45.0,
+    },
+];
+
+/// How a stat compares to its reference values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Verdict {
+    Strength,
+    Average,
+    Weakness,
+}
+
+/// A stat judged against its reference values
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Assessment {
+    pub label: String,
+    pub value: f64,
+    pub strong: f64,
+    pub weak: f64,
+    pub verdict: Verdict,
+}
+
+/// A labelled value of the rating radar (0 - 100)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RadarValue {
+    pub label: String,
+    pub value: f64,
+}
+
+/// Ranks of a player
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RankSummary {
+    pub leetify: Option<f64>,
+    pub premier: Option<u32>,
+    pub premier_tier: Option<PremierTier>,
+    pub faceit: Option<u32>,
+    pub faceit_elo: Option<u32>,
+    pub wingman: Option<u32>,
+    pub renown: Option<u32>,
+    pub competitive: Vec<CompetitiveRank>,
+}
+
+/// Everything shown in a player report; also the `report` value available to
+/// templates
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::{Client, Id};
+/// # use leetify::report::PlayerReport;
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let client = Client::new();
+/// let id = Id::Steam64("76561198283431555".into());
+///
+/// let profile = client.get_profile(id.clone()).await?;
+/// let matches = client.get_profile_matches(id).await?;
+///
+/// let report = PlayerReport::new(&profile, &matches);
+/// std::fs::write("report.html", report.to_html()?)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerReport {
+    pub name: String,
+    pub steam64_id: String,
+    pub leetify_id: Option<String>,
+    pub generated_at: DateTime<Utc>,
+    pub total_matches: u32,
+    pub winrate: f64,
+    pub radar: Vec<RadarValue>,
+    pub ranks: RankSummary,
+    /// Maps ordered by number of matches played
+    pub maps: Vec<MapPerformance>,
+    /// Leetify rating per match, oldest first
+    pub form: Vec<TimePoint>,
+    pub trend: Option<Trend>,
+    pub stats: Stats,
+    pub assessments: Vec<Assessment>,
+    pub bans: Vec<PlatformBanInfo>,
+}
+
+impl PlayerReport {
+    /// Build a report from a profile and the player's match history, judged
+    /// against [`DEFAULT_REFERENCES`]
+    pub fn new(profile: &ProfileResponse, matches: &[MatchDetailsResponse]) -> Self {
+        let rating = &profile.rating;
+        let radar = [
+            ("Aim", rating.aim),
+            ("Positioning", rating.positioning),
+            ("Utility", rating.utility),
+            ("Clutch", rating.clutch),
+            ("Opening", rating.opening),
+        ]
+        .into_iter()
+        .map(|(label, value)| RadarValue {
+            label: label.to_string(),
+            value,
+        })
+        .collect();
+
+        let ranks = &profile.ranks;
+        let form = Form::from_matches(&profile.steam64_id, matches);
+
+        Self {
+            name: profile.name.clone(),
+            steam64_id: profile.steam64_id.clone(),
+            leetify_id: profile.id.clone(),
+            generated_at: Utc::now(),
+            total_matches: profile.total_matches,
+            winrate: profile.winrate,
+            radar,
+            ranks: RankSummary {
+                leetify: ranks.leetify,
+                premier: ranks.premier,
+                premier_tier: ranks.premier.map(PremierTier::from_rating),
+                faceit: ranks.faceit,
+                faceit_elo: ranks.faceit_elo,
+                wingman: ranks.wingman,
+                renown: ranks.renown,
+                competitive: ranks.competitive.clone(),
+            },
+            maps: MapReport::new(&profile.steam64_id, matches, ranks).maps,
+            form: form
+                .samples()
+                .iter()
+                .filter_map(|s| {
+                    Some(TimePoint {
+                        finished_at: s.finished_at,
+                        value: s.leetify_rating?,
+                    })
+                })
+                .collect(),
+            trend: form.trend(Metric::LeetifyRating),
+            stats: profile.stats.clone(),
+            assessments: Vec::new(),
+            bans: profile.bans.clone(),
+        }
+        .assess(DEFAULT_REFERENCES)
+    }
+
+    /// Judge the stats against custom reference values instead
+    pub fn assess(mut self, references: &[Reference]) -> Self {
+        self.assessments = references
+            .iter()
+            .map(|reference| {
+                let value = (reference.value)(&self.stats);
+                Assessment {
+                    label: reference.label.to_string(),
+                    value,
+                    strong: reference.strong,
+                    weak: reference.weak,
+                    verdict: reference.verdict(value),
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Assessments with the given verdict
+    pub fn with_verdict(&self, verdict: Verdict) -> impl Iterator<Item = &Assessment> {
+        self.assessments
+            .iter()
+            .filter(move |a| a.verdict == verdict)
+    }
+
+    /// Render with the built-in HTML template
+    pub fn to_html(&self) -> Result<String, Error> {
+        self.render_template(HTML_TEMPLATE, ReportFormat::Html)
+    }
+
+    /// Render with the built-in Markdown template
+    pub fn to_markdown(&self) -> Result<String, Error> {
+        self.render_template(MARKDOWN_TEMPLATE, ReportFormat::Markdown)
+    }
+
+    /// Render with a custom template
+    ///
+    /// Templates get the report as `report`, the charts as `radar_svg` and
+    /// `form_svg` (inline SVG) and the form as `form_sparkline` (text).
+    pub fn render_template(&self, source: &str, format: ReportFormat) -> Result<String, Error> {
+        let radar: Vec<_> = self
+            .radar
+            .iter()
+            .map(|r| (r.label.clone(), r.value))
+            .collect();
+
+        let ctx = context! {
+            report => Value::from_serialize(self),
+            radar_svg => Value::from_safe_string(svg::radar(&radar)),
+            form_svg => Value::from_safe_string(svg::line_chart(&self.form)),
+            form_sparkline => svg::sparkline(&self.form),
+        };
+        Ok(Environment::new().render_named_str(format.template_name(), source, ctx)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PlayerStats, Ranks, Rating};
+
+    fn profile() -> ProfileResponse {
+        ProfileResponse {
+            name: "<script>".to_string(),
+            steam64_id: "1".to_string(),
+            total_matches: 2,
+            ranks: Ranks {
+                premier: Some(15_500),
+                ..Default::default()
+            },
+            rating: Rating {
+                aim: 80.0,
+                ..Default::default()
+            },
+            stats: Stats {
+                accuracy_head: 30.0,
+                reaction_time_ms: 800.0,
+                preaim: 9.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn matches() -> Vec<MatchDetailsResponse> {
+        (0..2)
+            .map(|i| MatchDetailsResponse {
+                id: format!("m{i}"),
+                finished_at: DateTime::from_timestamp(i * 86_400, 0).unwrap(),
+                map_name: "de_nuke".to_string(),
+                stats: vec![PlayerStats {
+                    steam64_id: "1".to_string(),
+                    rounds_won: 13,
+                    rounds_lost: 7,
+                    leetify_rating: Some(0.02 * i as f64 - 0.01),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_assessments() {
+        let report = PlayerReport::new(&profile(), &matches());
+        let verdict = |label: &str| {
+            report
+                .assessments
+                .iter()
+                .find(|a| a.label.starts_with(label))
+                .unwrap()
+                .verdict
+        };
+        assert_eq!(verdict("Head accuracy"), Verdict::Strength);
+        assert_eq!(verdict("Reaction time"), Verdict::Weakness);
+        assert_eq!(verdict("Crosshair placement"), Verdict::Average);
+        assert_eq!(report.ranks.premier_tier, Some(PremierTier::Purple));
+        assert_eq!(report.form.len(), 2);
+    }
+
+    #[test]
+    fn test_html_report() {
+        let html = PlayerReport::new(&profile(), &matches()).to_html().unwrap();
+        assert!(html.contains("<h1>&lt;script&gt;</h1>"));
+        assert!(html.contains("<svg"));
+        assert!(html.contains("15500 (Purple)"));
+        assert!(html.contains("<td>de_nuke</td>"));
+        assert!(html.contains("No bans on record."));
+    }
+
+    #[test]
+    fn test_markdown_and_custom_templates() {
+        let report = PlayerReport::new(&profile(), &matches());
+        let markdown = report.to_markdown().unwrap();
+        assert!(markdown.starts_with("# <script>\n"));
+        assert!(markdown.contains("| de_nuke* | 2 | 100% |"));
+        assert!(markdown.contains("`▁█`"));
+
+        let custom = report
+            .render_template(
+                "{{ report.name }}: {{ report.maps|length }} maps",
+                ReportFormat::Html,
+            )
+            .unwrap();
+        assert_eq!(custom, "&lt;script&gt;: 1 maps");
+    }
+}
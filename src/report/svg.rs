@@ -0,0 +1,132 @@
+//! Inline SVG charts for reports
+
+use crate::analysis::TimePoint;
+use std::fmt::Write;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Radar chart of values on a 0 - 100 scale
+pub(crate) fn radar(values: &[(String, f64)]) -> String {
+    const SIZE: f64 = 260.0;
+    const CENTER: f64 = SIZE / 2.0;
+    const RADIUS: f64 = 90.0;
+
+    let point = |i: usize, fraction: f64| {
+        let angle = -std::f64::consts::FRAC_PI_2
+            + i as f64 * std::f64::consts::TAU / values.len().max(1) as f64;
+        (
+            CENTER + RADIUS * fraction * angle.cos(),
+            CENTER + RADIUS * fraction * angle.sin(),
+        )
+    };
+    let polygon = |fractions: &mut dyn Iterator<Item = f64>| {
+        let points: Vec<_> = fractions
+            .enumerate()
+            .map(|(i, f)| {
+                let (x, y) = point(i, f);
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect();
+        points.join(" ")
+    };
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {size} {size}" width="{size}" height="{size}" class="radar">"#,
+        size = SIZE
+    );
+    for ring in [0.25, 0.5, 0.75, 1.0] {
+        let _ = write!(
+            svg,
+            r##"<polygon points="{}" fill="none" stroke="#ccc"/>"##,
+            polygon(&mut std::iter::repeat_n(ring, values.len()))
+        );
+    }
+    for (i, (label, _)) in values.iter().enumerate() {
+        let (x, y) = point(i, 1.0);
+        let (lx, ly) = point(i, 1.25);
+        let _ = write!(
+            svg,
+            r##"<line x1="{CENTER}" y1="{CENTER}" x2="{x:.1}" y2="{y:.1}" stroke="#ccc"/><text x="{lx:.1}" y="{ly:.1}" font-size="11" text-anchor="middle" dominant-baseline="middle">{}</text>"##,
+            escape(label)
+        );
+    }
+    let _ = write!(
+        svg,
+        r##"<polygon points="{}" fill="rgba(246,140,0,0.35)" stroke="#f68c00" stroke-width="2"/></svg>"##,
+        polygon(&mut values.iter().map(|(_, v)| (v / 100.0).clamp(0.0, 1.0)))
+    );
+    svg
+}
+
+/// Line chart of a series with a zero line
+pub(crate) fn line_chart(points: &[TimePoint]) -> String {
+    const WIDTH: f64 = 480.0;
+    const HEIGHT: f64 = 160.0;
+    const PADDING: f64 = 10.0;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {WIDTH} {HEIGHT}" width="{WIDTH}" height="{HEIGHT}" class="form">"#
+    );
+    if points.is_empty() {
+        svg.push_str("</svg>");
+        return svg;
+    }
+
+    let low = points.iter().map(|p| p.value).fold(0.0_f64, f64::min);
+    let high = points.iter().map(|p| p.value).fold(0.0_f64, f64::max);
+    let span = (high - low).max(f64::EPSILON);
+    let step = (WIDTH - 2.0 * PADDING) / (points.len().max(2) - 1) as f64;
+    let y = |value: f64| HEIGHT - PADDING - (value - low) / span * (HEIGHT - 2.0 * PADDING);
+
+    let _ = write!(
+        svg,
+        r##"<line x1="0" y1="{zero:.1}" x2="{WIDTH}" y2="{zero:.1}" stroke="#ccc" stroke-dasharray="4 4"/>"##,
+        zero = y(0.0)
+    );
+    let line: Vec<_> = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| format!("{:.1},{:.1}", PADDING + i as f64 * step, y(p.value)))
+        .collect();
+    let _ = write!(
+        svg,
+        r##"<polyline points="{}" fill="none" stroke="#f68c00" stroke-width="2"/>"##,
+        line.join(" ")
+    );
+    for (i, p) in points.iter().enumerate() {
+        let color = if p.value >= 0.0 { "#2e9e44" } else { "#d33" };
+        let _ = write!(
+            svg,
+            r#"<circle cx="{:.1}" cy="{:.1}" r="3" fill="{color}"><title>{} {:+.2}</title></circle>"#,
+            PADDING + i as f64 * step,
+            y(p.value),
+            p.finished_at.format("%Y-%m-%d"),
+            p.value
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Unicode sparkline of a series, for text formats
+pub(crate) fn sparkline(points: &[TimePoint]) -> String {
+    let low = points.iter().map(|p| p.value).fold(f64::INFINITY, f64::min);
+    let high = points
+        .iter()
+        .map(|p| p.value)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let span = (high - low).max(f64::EPSILON);
+    points
+        .iter()
+        .map(|p| {
+            let level = ((p.value - low) / span * (SPARKS.len() - 1) as f64).round() as usize;
+            SPARKS[level.min(SPARKS.len() - 1)]
+        })
+        .collect()
+}
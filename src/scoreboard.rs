@@ -0,0 +1,324 @@
+//! CS-style scoreboards for terminals and chat
+
+use crate::types::{MatchDetailsResponse, PlayerStats, TeamScore};
+use std::cmp::Reverse;
+use std::fmt;
+
+/// How a [`Scoreboard`] is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Aligned text with ANSI colors, for terminals
+    Ansi,
+    /// Aligned text without colors
+    Plain,
+    /// Bold title and the plain table in a code block, for Discord and
+    /// other Markdown chats
+    Markdown,
+}
+
+const HEADER: [&str; 12] = [
+    "Player", "K", "A", "D", "+/-", "ADR", "HS%", "Rating", "MVP", "3K", "4K", "5K",
+];
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+struct Cell {
+    text: String,
+    color: Option<&'static str>,
+}
+
+impl Cell {
+    fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            color: None,
+        }
+    }
+
+    fn color(mut self, color: &'static str) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Color positive values green and negative values red
+    fn signed(value: f64, text: String) -> Self {
+        let cell = Cell::new(text);
+        if value > 0.0 {
+            cell.color(GREEN)
+        } else if value < 0.0 {
+            cell.color(RED)
+        } else {
+            cell
+        }
+    }
+}
+
+/// Scoreboard of a match: both teams, winners first, with players sorted by
+/// score
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::Client;
+/// # use leetify::scoreboard::{Scoreboard, Style};
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let client = Client::new();
+/// let details = client.get_match_by_game_id("match-id-123".to_string()).await?;
+///
+/// // Colored for a terminal, with one player in bold
+/// print!("{}", Scoreboard::new(&details).highlight("76561198283431555").render(Style::Ansi));
+///
+/// // Code block for a Discord message
+/// let message = Scoreboard::new(&details).render(Style::Markdown);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scoreboard<'a> {
+    details: &'a MatchDetailsResponse,
+    highlight: Option<String>,
+}
+
+impl<'a> Scoreboard<'a> {
+    pub fn new(details: &'a MatchDetailsResponse) -> Self {
+        Self {
+            details,
+            highlight: None,
+        }
+    }
+
+    /// Emphasize a player by Steam64 ID
+    pub fn highlight(mut self, steam64_id: impl Into<String>) -> Self {
+        self.highlight = Some(steam64_id.into());
+        self
+    }
+
+    /// Team scores, winning team first
+    fn teams(&self) -> [&'a TeamScore; 2] {
+        let [a, b] = &self.details.team_scores;
+        if b.score > a.score { [b, a] } else { [a, b] }
+    }
+
+    fn title(&self) -> String {
+        let [first, second] = self.teams();
+        format!(
+            "{} ({}) {} : {}",
+            self.details.map_name, self.details.data_source, first.score, second.score
+        )
+    }
+
+    fn player_row(&self, stats: &PlayerStats, top_fragger: bool) -> Vec<Cell> {
+        let diff = stats.total_kills as i64 - stats.total_deaths as i64;
+        let hs = if stats.total_kills == 0 {
+            0.0
+        } else {
+            stats.total_hs_kills as f64 / stats.total_kills as f64 * 100.0
+        };
+        let rating = match stats.leetify_rating {
+            Some(rating) => Cell::signed(rating, format!("{:+.2}", rating)),
+            None => Cell::new("-").color(DIM),
+        };
+        let kills = Cell::new(stats.total_kills.to_string());
+
+        vec![
+            Cell::new(stats.name.clone()),
+            if top_fragger {
+                kills.color(YELLOW)
+            } else {
+                kills
+            },
+            Cell::new(stats.total_assists.to_string()),
+            Cell::new(stats.total_deaths.to_string()),
+            Cell::signed(diff as f64, format!("{:+}", diff)),
+            Cell::new(format!("{:.0}", stats.dpr)),
+            Cell::new(format!("{:.0}", hs)),
+            rating,
+            Cell::new(stats.mvps.to_string()),
+            Cell::new(stats.multi3k.to_string()),
+            Cell::new(stats.multi4k.to_string()),
+            Cell::new(stats.multi5k.to_string()),
+        ]
+    }
+
+    /// Section title and rows of each team, winners first
+    fn sections(&self) -> Vec<(Cell, Vec<Vec<Cell>>)> {
+        let [first, second] = self.teams();
+        let top_kills = self.details.stats.iter().map(|s| s.total_kills).max();
+
+        [first, second]
+            .into_iter()
+            .map(|team| {
+                let title = Cell::new(format!("Team {} - {}", team.team_number, team.score));
+                let title = match first.score.cmp(&second.score) {
+                    std::cmp::Ordering::Equal => title.color(YELLOW),
+                    _ if std::ptr::eq(team, first) => title.color(GREEN),
+                    _ => title.color(RED),
+                };
+
+                let mut players: Vec<_> = self
+                    .details
+                    .stats
+                    .iter()
+                    .filter(|s| s.initial_team_number == team.team_number)
+                    .collect();
+                players.sort_by_key(|s| (Reverse(s.score), Reverse(s.total_kills)));
+
+                let rows = players
+                    .into_iter()
+                    .map(|s| {
+                        let highlighted = self.highlight.as_deref() == Some(s.steam64_id.as_str());
+                        let top = top_kills == Some(s.total_kills) && s.total_kills > 0;
+                        let mut row = self.player_row(s, top);
+                        if highlighted {
+                            row[0].color = Some(BOLD);
+                        }
+                        row
+                    })
+                    .collect();
+                (title, rows)
+            })
+            .collect()
+    }
+
+    /// Render the scoreboard as text ending with a newline
+    pub fn render(&self, style: Style) -> String {
+        let colored = style == Style::Ansi;
+        let sections = self.sections();
+
+        let mut widths: Vec<usize> = HEADER.iter().map(|h| h.chars().count()).collect();
+        for (_, rows) in &sections {
+            for row in rows {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.text.chars().count());
+                }
+            }
+        }
+
+        let paint = |text: &str, color: Option<&str>| match color {
+            Some(color) if colored => format!("{}{}{}", color, text, RESET),
+            _ => text.to_string(),
+        };
+        let line = |cells: &[Cell]| {
+            let padded: Vec<_> = cells
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(i, (cell, &width))| {
+                    // Names are left-aligned, numbers right-aligned
+                    let text = if i == 0 {
+                        format!("{:<width$}", cell.text)
+                    } else {
+                        format!("{:>width$}", cell.text)
+                    };
+                    paint(&text, cell.color)
+                })
+                .collect();
+            padded.join("  ")
+        };
+
+        let mut out = String::new();
+        if style == Style::Markdown {
+            out.push_str(&format!("**{}**\n```\n", self.title()));
+        } else {
+            out.push_str(&paint(&self.title(), Some(BOLD)));
+            out.push('\n');
+        }
+
+        let header: Vec<_> = HEADER.iter().map(|h| Cell::new(*h)).collect();
+        for (title, rows) in sections {
+            out.push('\n');
+            out.push_str(&paint(&title.text, title.color));
+            out.push('\n');
+            out.push_str(line(&header).trim_end());
+            out.push('\n');
+            for row in rows {
+                out.push_str(line(&row).trim_end());
+                out.push('\n');
+            }
+        }
+
+        if style == Style::Markdown {
+            out.push_str("```\n");
+        }
+        out
+    }
+}
+
+impl fmt::Display for Scoreboard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(Style::Plain))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game() -> MatchDetailsResponse {
+        let player = |name: &str, team: u32, kills: u32, score: u32| PlayerStats {
+            steam64_id: name.to_string(),
+            name: name.to_string(),
+            initial_team_number: team,
+            total_kills: kills,
+            total_hs_kills: kills / 2,
+            total_deaths: 10,
+            score,
+            dpr: 85.4,
+            leetify_rating: Some(if team == 3 { 0.04 } else { -0.02 }),
+            ..Default::default()
+        };
+        MatchDetailsResponse {
+            map_name: "de_nuke".to_string(),
+            data_source: "faceit".to_string(),
+            team_scores: [
+                TeamScore {
+                    team_number: 2,
+                    score: 7,
+                },
+                TeamScore {
+                    team_number: 3,
+                    score: 13,
+                },
+            ],
+            stats: vec![
+                player("loser", 2, 12, 30),
+                player("carry", 3, 30, 70),
+                player("support", 3, 8, 40),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plain_scoreboard() {
+        let details = game();
+        let text = Scoreboard::new(&details).to_string();
+        let lines: Vec<_> = text.lines().collect();
+
+        assert_eq!(lines[0], "de_nuke (faceit) 13 : 7");
+        assert_eq!(lines[2], "Team 3 - 13");
+        assert!(lines[4].starts_with("carry    30  0  10  +20   85   50   +0.04"));
+        assert!(lines[5].starts_with("support"));
+        assert_eq!(lines[7], "Team 2 - 7");
+        assert!(!text.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_ansi_and_markdown() {
+        let details = game();
+        let ansi = Scoreboard::new(&details)
+            .highlight("carry")
+            .render(Style::Ansi);
+        assert!(ansi.contains(&format!("{}Team 3 - 13{}", GREEN, RESET)));
+        assert!(ansi.contains(&format!("{}carry", BOLD)));
+
+        let markdown = Scoreboard::new(&details).render(Style::Markdown);
+        assert!(markdown.starts_with("**de_nuke (faceit) 13 : 7**\n```\n"));
+        assert!(markdown.ends_with("```\n"));
+    }
+}
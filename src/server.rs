@@ -0,0 +1,651 @@
+//! Caching gateway that serves the Leetify API to internal consumers
+//!
+//! The gateway exposes the same routes as the public API and forwards them
+//! through a single [`Client`], so every consumer shares one API key and one
+//! quota. Responses are cached, identical concurrent requests share one
+//! upstream request, and upstream requests are spaced out to stay within a
+//! rate limit.
+
+use crate::client::Client;
+use crate::error::Error;
+use crate::types::{DataSource, Id, LeetifyId, Steam64Id};
+use axum::Router;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+/// Default lifetime of cached profiles and match histories
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// Default lifetime of cached match details, which do not change
+const DEFAULT_MATCH_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Default upper bound for the number of cached responses
+const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+/// Longest interval between upstream requests, i.e. one request per hour
+const MAX_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// An upstream request
+#[derive(Debug, Clone)]
+enum Upstream {
+    Profile(Id),
+    ProfileMatches(Id),
+    Match(String),
+    MatchByDataSource(DataSource, String),
+}
+
+impl Upstream {
+    /// Cache key; identical for requests with the same upstream response
+    fn key(&self) -> String {
+        let id = |id: &Id| match id {
+            Id::Steam64(id) => format!("steam64_id={}", id.as_ref()),
+            Id::Leetify(id) => format!("id={}", id.as_ref()),
+        };
+        match self {
+            Upstream::Profile(player) => format!("/v3/profile?{}", id(player)),
+            Upstream::ProfileMatches(player) => format!("/v3/profile/matches?{}", id(player)),
+            Upstream::Match(game_id) => format!("/v2/matches/{}", game_id),
+            Upstream::MatchByDataSource(source, match_id) => {
+                format!("/v2/matches/{}/{}", source.as_str(), match_id)
+            }
+        }
+    }
+
+    fn is_match(&self) -> bool {
+        matches!(self, Upstream::Match(_) | Upstream::MatchByDataSource(..))
+    }
+
+    async fn fetch(&self, client: &Client) -> Result<String, Error> {
+        Ok(match self.clone() {
+            Upstream::Profile(id) => serde_json::to_string(&client.get_profile(id).await?)?,
+            Upstream::ProfileMatches(id) => {
+                serde_json::to_string(&client.get_profile_matches(id).await?)?
+            }
+            Upstream::Match(game_id) => {
+                serde_json::to_string(&client.get_match_by_game_id(game_id).await?)?
+            }
+            Upstream::MatchByDataSource(source, match_id) => {
+                serde_json::to_string(&client.get_match_by_data_source(source, match_id).await?)?
+            }
+        })
+    }
+}
+
+/// A failed upstream request as it is served to consumers
+#[derive(Debug, Clone)]
+struct Failure {
+    status: StatusCode,
+    message: String,
+    retry_after: Option<Duration>,
+}
+
+impl From<Error> for Failure {
+    fn from(error: Error) -> Self {
+        let status = match &error {
+            Error::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            Error::MissingParameter(_) | Error::InvalidGameId(_) | Error::InvalidDataSource(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            Error::Api(status, _) if (400..500).contains(status) => {
+                StatusCode::from_u16(*status).unwrap_or(StatusCode::BAD_GATEWAY)
+            }
+            Error::Http(e) if e.is_timeout() => StatusCode::GATEWAY_TIMEOUT,
+            _ => StatusCode::BAD_GATEWAY,
+        };
+        let retry_after = match &error {
+            Error::RateLimited(retry_after) => *retry_after,
+            _ => None,
+        };
+        Self {
+            status,
+            message: error.to_string(),
+            retry_after,
+        }
+    }
+}
+
+impl IntoResponse for Failure {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({ "error": self.message }).to_string();
+        let mut response = (
+            self.status,
+            [(header::CONTENT_TYPE, "application/json")],
+            body,
+        )
+            .into_response();
+        if let Some(retry_after) = self.retry_after {
+            response.headers_mut().insert(
+                header::RETRY_AFTER,
+                HeaderValue::from(retry_after.as_secs().max(1)),
+            );
+        }
+        response
+    }
+}
+
+type Flight = Arc<OnceCell<Result<Arc<str>, Failure>>>;
+
+struct Entry {
+    body: Arc<str>,
+    expires: Instant,
+}
+
+/// Spaces out upstream requests and pauses them after a 429
+struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// Wait for the next free slot
+    async fn acquire(&self) {
+        let slot = {
+            let mut next = self.next.lock().unwrap_or_else(|e| e.into_inner());
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot.into()).await;
+    }
+
+    /// Hold back all upstream requests for `delay`
+    fn pause(&self, delay: Duration) {
+        let mut next = self.next.lock().unwrap_or_else(|e| e.into_inner());
+        *next = (*next).max(Instant::now() + delay);
+    }
+}
+
+/// Counters of a running gateway
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct GatewayStats {
+    /// Requests answered from the cache
+    pub hits: u64,
+    /// Requests that joined an identical in-flight request
+    pub coalesced: u64,
+    /// Requests forwarded to the API
+    pub upstream: u64,
+}
+
+struct GatewayInner {
+    client: Client,
+    ttl: Duration,
+    match_ttl: Duration,
+    max_entries: usize,
+    limiter: RateLimiter,
+    cache: Mutex<HashMap<String, Entry>>,
+    in_flight: Mutex<HashMap<String, Flight>>,
+    stats: Mutex<GatewayStats>,
+}
+
+/// Builder for a [`Gateway`]
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::Client;
+/// # use leetify::server::Gateway;
+/// # use std::time::Duration;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Client::with_api_key("your-api-key".to_string());
+/// let gateway = Gateway::builder(client)
+///     .ttl(Duration::from_secs(120))
+///     .requests_per_second(5.0)
+///     .build();
+///
+/// let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await?;
+/// axum::serve(listener, gateway.router()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct GatewayBuilder {
+    client: Client,
+    ttl: Duration,
+    match_ttl: Duration,
+    max_entries: usize,
+    interval: Duration,
+}
+
+impl GatewayBuilder {
+    /// Builder with a 60 second cache for profiles and match histories, a
+    /// 24 hour cache for match details and at most 10 upstream requests per
+    /// second
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            ttl: DEFAULT_TTL,
+            match_ttl: DEFAULT_MATCH_TTL,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            interval: Duration::from_millis(100),
+        }
+    }
+
+    /// How long profiles and match histories are cached
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// How long match details are cached
+    pub fn match_ttl(mut self, ttl: Duration) -> Self {
+        self.match_ttl = ttl;
+        self
+    }
+
+    /// Upper bound for the number of cached responses
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Upper bound for upstream requests per second
+    ///
+    /// Rates below one request per hour, including zero, negative and NaN
+    /// rates, are clamped to one request per hour.
+    pub fn requests_per_second(mut self, rate: f64) -> Self {
+        self.interval = Duration::try_from_secs_f64(1.0 / rate)
+            .ok()
+            .filter(|interval| *interval <= MAX_INTERVAL)
+            .unwrap_or(MAX_INTERVAL);
+        self
+    }
+
+    /// Build the gateway
+    pub fn build(self) -> Gateway {
+        Gateway {
+            inner: Arc::new(GatewayInner {
+                client: self.client,
+                ttl: self.ttl,
+                match_ttl: self.match_ttl,
+                max_entries: self.max_entries,
+                limiter: RateLimiter {
+                    interval: self.interval,
+                    next: Mutex::new(Instant::now()),
+                },
+                cache: Mutex::new(HashMap::new()),
+                in_flight: Mutex::new(HashMap::new()),
+                stats: Mutex::new(GatewayStats::default()),
+            }),
+        }
+    }
+
+    /// Build the gateway and return its routes
+    pub fn router(self) -> Router {
+        self.build().router()
+    }
+}
+
+/// Caching, coalescing and rate-limited gateway in front of the Leetify API
+///
+/// Serves `/v3/profile`, `/v3/profile/matches`, `/v2/matches/{game_id}` and
+/// `/v2/matches/{data_source}/{data_source_id}` like the public API, plus
+/// `/health` and `/stats`. Consumers don't send an API key; the gateway's
+/// client uses its own. Use [`Gateway::builder`] to change the cache
+/// lifetimes or the rate limit.
+#[derive(Clone)]
+pub struct Gateway {
+    inner: Arc<GatewayInner>,
+}
+
+impl Gateway {
+    /// Gateway with a 60 second cache for profiles and match histories, a
+    /// 24 hour cache for match details and at most 10 upstream requests per
+    /// second
+    pub fn new(client: Client) -> Self {
+        GatewayBuilder::new(client).build()
+    }
+
+    /// Builder for a gateway with custom settings
+    pub fn builder(client: Client) -> GatewayBuilder {
+        GatewayBuilder::new(client)
+    }
+
+    /// Current counters
+    pub fn stats(&self) -> GatewayStats {
+        *self.inner.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Routes of the gateway
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/v3/profile", get(profile))
+            .route("/v3/profile/matches", get(profile_matches))
+            .route("/v2/matches/{game_id}", get(match_by_game_id))
+            .route(
+                "/v2/matches/{data_source}/{data_source_id}",
+                get(match_by_data_source),
+            )
+            .route("/health", get(|| async { "ok" }))
+            .route("/stats", get(stats))
+            .with_state(self.clone())
+    }
+
+    fn count(&self, f: impl FnOnce(&mut GatewayStats)) {
+        f(&mut self.inner.stats.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    fn in_flight(&self) -> MutexGuard<'_, HashMap<String, Flight>> {
+        self.inner
+            .in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn cached(&self, key: &str) -> Option<Arc<str>> {
+        let cache = self.inner.cache.lock().unwrap_or_else(|e| e.into_inner());
+        cache
+            .get(key)
+            .filter(|entry| entry.expires > Instant::now())
+            .map(|entry| entry.body.clone())
+    }
+
+    fn store(&self, key: String, body: Arc<str>, ttl: Duration) {
+        let mut cache = self.inner.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if cache.len() >= self.inner.max_entries {
+            let now = Instant::now();
+            cache.retain(|_, entry| entry.expires > now);
+            if cache.len() >= self.inner.max_entries {
+                cache.clear();
+            }
+        }
+        cache.insert(
+            key,
+            Entry {
+                body,
+                expires: Instant::now() + ttl,
+            },
+        );
+    }
+
+    async fn serve(&self, upstream: Upstream) -> Response {
+        let key = upstream.key();
+        if let Some(body) = self.cached(&key) {
+            self.count(|s| s.hits += 1);
+            return json(body, "HIT");
+        }
+
+        let (flight, leader) = {
+            let mut in_flight = self.in_flight();
+            match in_flight.get(&key) {
+                Some(flight) => (flight.clone(), false),
+                None => {
+                    let flight = Flight::default();
+                    in_flight.insert(key.clone(), flight.clone());
+                    (flight, true)
+                }
+            }
+        };
+
+        // Whoever runs the request removes the flight once it is done, so a
+        // leader that is dropped midway (e.g. by a client disconnect) does not
+        // leave the result behind for later requests. A successful response
+        // is cached first, so no request finds neither the cache entry nor
+        // the flight and goes upstream again.
+        let result = flight
+            .get_or_init(|| async {
+                self.count(|s| s.upstream += 1);
+                self.inner.limiter.acquire().await;
+                let result = match upstream.fetch(&self.inner.client).await {
+                    Ok(body) => {
+                        let body: Arc<str> = body.into();
+                        let ttl = if upstream.is_match() {
+                            self.inner.match_ttl
+                        } else {
+                            self.inner.ttl
+                        };
+                        self.store(key.clone(), body.clone(), ttl);
+                        Ok(body)
+                    }
+                    Err(error) => {
+                        if let Error::RateLimited(retry_after) = &error {
+                            self.inner
+                                .limiter
+                                .pause(retry_after.unwrap_or(Duration::from_secs(1)));
+                        }
+                        Err(Failure::from(error))
+                    }
+                };
+                let mut in_flight = self.in_flight();
+                if in_flight.get(&key).is_some_and(|f| Arc::ptr_eq(f, &flight)) {
+                    in_flight.remove(&key);
+                }
+                result
+            })
+            .await
+            .clone();
+
+        if !leader {
+            self.count(|s| s.coalesced += 1);
+        }
+
+        match result {
+            Ok(body) => json(body, "MISS"),
+            Err(failure) => failure.into_response(),
+        }
+    }
+}
+
+fn json(body: Arc<str>, cache: &'static str) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "application/json"),
+            (header::HeaderName::from_static("x-cache"), cache),
+        ],
+        body.to_string(),
+    )
+        .into_response()
+}
+
+/// Read the player ID from `steam64_id` or `id` query parameters
+fn player_id(query: &HashMap<String, String>) -> Result<Id, Failure> {
+    if let Some(id) = query.get("steam64_id") {
+        Ok(Id::Steam64(Steam64Id(id.clone())))
+    } else if let Some(id) = query.get("id") {
+        Ok(Id::Leetify(LeetifyId(id.clone())))
+    } else {
+        Err(Error::MissingParameter("steam64_id or id".to_string()).into())
+    }
+}
+
+async fn profile(
+    State(gateway): State<Gateway>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    match player_id(&query) {
+        Ok(id) => gateway.serve(Upstream::Profile(id)).await,
+        Err(failure) => failure.into_response(),
+    }
+}
+
+async fn profile_matches(
+    State(gateway): State<Gateway>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    match player_id(&query) {
+        Ok(id) => gateway.serve(Upstream::ProfileMatches(id)).await,
+        Err(failure) => failure.into_response(),
+    }
+}
+
+async fn match_by_game_id(State(gateway): State<Gateway>, Path(game_id): Path<String>) -> Response {
+    gateway.serve(Upstream::Match(game_id)).await
+}
+
+async fn match_by_data_source(
+    State(gateway): State<Gateway>,
+    Path((data_source, data_source_id)): Path<(String, String)>,
+) -> Response {
+    gateway
+        .serve(Upstream::MatchByDataSource(
+            DataSource::from(data_source),
+            data_source_id,
+        ))
+        .await
+}
+
+async fn stats(State(gateway): State<Gateway>) -> Response {
+    axum::Json(gateway.stats()).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{MatchDetailsResponse, ProfileResponse};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Serve a router on a random local port and return its base URL
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        url
+    }
+
+    /// Stand-in for the Leetify API that counts requests
+    async fn upstream(hits: Arc<AtomicUsize>) -> String {
+        let router = Router::new()
+            .route(
+                "/v3/profile",
+                get(|State(hits): State<Arc<AtomicUsize>>| async move {
+                    hits.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    axum::Json(ProfileResponse {
+                        name: "gateway".to_string(),
+                        ..Default::default()
+                    })
+                }),
+            )
+            .route(
+                "/v2/matches/{game_id}",
+                get(|Path(game_id): Path<String>| async move {
+                    if game_id == "limited" {
+                        (
+                            StatusCode::TOO_MANY_REQUESTS,
+                            [(header::RETRY_AFTER, "1")],
+                            "",
+                        )
+                            .into_response()
+                    } else {
+                        axum::Json(MatchDetailsResponse {
+                            id: game_id,
+                            ..Default::default()
+                        })
+                        .into_response()
+                    }
+                }),
+            )
+            .with_state(hits);
+        serve(router).await
+    }
+
+    async fn gateway(hits: Arc<AtomicUsize>) -> (Gateway, String) {
+        let client = Client::builder()
+            .base_url(upstream(hits).await)
+            .build()
+            .unwrap();
+        let gateway = Gateway::builder(client).requests_per_second(1000.0).build();
+        let url = serve(gateway.router()).await;
+        (gateway, url)
+    }
+
+    #[tokio::test]
+    async fn test_cache_and_coalescing() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let (gateway, url) = gateway(hits.clone()).await;
+        let http = reqwest::Client::new();
+        let url = format!("{}/v3/profile?steam64_id=76561198283431555", url);
+
+        let requests: Vec<_> = (0..5)
+            .map(|_| tokio::spawn(http.get(&url).send()))
+            .collect();
+        for request in requests {
+            let response = request.await.unwrap().unwrap();
+            assert_eq!(response.status(), 200);
+            let profile: ProfileResponse = response.json().await.unwrap();
+            assert_eq!(profile.name, "gateway");
+        }
+
+        let response = http.get(&url).send().await.unwrap();
+        assert_eq!(response.headers()["x-cache"], "HIT");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let stats = gateway.stats();
+        assert_eq!(stats.upstream, 1);
+        assert_eq!(stats.coalesced + stats.hits, 5);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_leader() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let client = Client::builder()
+            .base_url(upstream(hits.clone()).await)
+            .build()
+            .unwrap();
+        let gateway = Gateway::builder(client)
+            .ttl(Duration::ZERO)
+            .requests_per_second(1000.0)
+            .build();
+        let profile = || Upstream::Profile(Id::Steam64("76561198283431555".into()));
+
+        // The upstream takes 50ms, so the leader is dropped mid-flight
+        let dropped =
+            tokio::time::timeout(Duration::from_millis(10), gateway.serve(profile())).await;
+        assert!(dropped.is_err());
+
+        for expected_hits in [2, 3] {
+            let response = gateway.serve(profile()).await;
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.headers()["x-cache"], "MISS");
+            assert_eq!(hits.load(Ordering::SeqCst), expected_hits);
+            assert!(gateway.in_flight().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_requests_per_second() {
+        let interval = |rate| {
+            Gateway::builder(Client::new())
+                .requests_per_second(rate)
+                .interval
+        };
+        assert_eq!(interval(4.0), Duration::from_millis(250));
+        assert_eq!(interval(f64::INFINITY), Duration::ZERO);
+        for rate in [0.0, -1.0, f64::NAN, 1e-300] {
+            assert_eq!(interval(rate), MAX_INTERVAL);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let (_, url) = gateway(Arc::new(AtomicUsize::new(0))).await;
+        let http = reqwest::Client::new();
+
+        let response = http
+            .get(format!("{}/v3/profile", url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+
+        let response = http
+            .get(format!("{}/v2/matches/limited", url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 429);
+        assert_eq!(response.headers()["retry-after"], "1");
+
+        let response = http
+            .get(format!("{}/v2/matches/m1", url))
+            .send()
+            .await
+            .unwrap();
+        let details: MatchDetailsResponse = response.json().await.unwrap();
+        assert_eq!(details.id, "m1");
+    }
+}
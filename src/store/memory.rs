@@ -0,0 +1,78 @@
+use super::{ProfileSnapshot, Store, SyncCheckpoint};
+use crate::error::Error;
+use crate::types::{MatchDetailsResponse, ProfileResponse};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// Store that keeps everything in memory
+///
+/// Useful for tests and short-lived jobs; nothing survives a restart.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    profiles: HashMap<String, Vec<ProfileSnapshot>>,
+    matches: HashMap<String, MatchDetailsResponse>,
+    checkpoints: HashMap<String, SyncCheckpoint>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get all snapshots of a player's profile, oldest first
+    pub fn profile_history(&self, steam64_id: &str) -> &[ProfileSnapshot] {
+        self.profiles
+            .get(steam64_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Get a stored match by its Leetify match ID
+    pub fn get_match(&self, match_id: &str) -> Option<&MatchDetailsResponse> {
+        self.matches.get(match_id)
+    }
+
+    /// Iterate over all stored matches in no particular order
+    pub fn matches(&self) -> impl Iterator<Item = &MatchDetailsResponse> {
+        self.matches.values()
+    }
+}
+
+impl Store for MemoryStore {
+    fn save_profile(
+        &mut self,
+        profile: &ProfileResponse,
+        fetched_at: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        let history = self.profiles.entry(profile.steam64_id.clone()).or_default();
+        history.push(ProfileSnapshot {
+            fetched_at,
+            profile: profile.clone(),
+        });
+        history.sort_by_key(|s| s.fetched_at);
+        Ok(())
+    }
+
+    fn save_match(&mut self, details: &MatchDetailsResponse) -> Result<(), Error> {
+        self.matches.insert(details.id.clone(), details.clone());
+        Ok(())
+    }
+
+    fn has_match(&self, match_id: &str) -> Result<bool, Error> {
+        Ok(self.matches.contains_key(match_id))
+    }
+
+    fn checkpoint(&self, steam64_id: &str) -> Result<Option<SyncCheckpoint>, Error> {
+        Ok(self.checkpoints.get(steam64_id).cloned())
+    }
+
+    fn save_checkpoint(
+        &mut self,
+        steam64_id: &str,
+        checkpoint: &SyncCheckpoint,
+    ) -> Result<(), Error> {
+        self.checkpoints
+            .insert(steam64_id.to_string(), checkpoint.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+//! Persistence of profiles and matches for offline analysis
+
+pub mod memory;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+pub use memory::MemoryStore;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
+
+use crate::error::Error;
+use crate::types::{MatchDetailsResponse, ProfileResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Storage backend used by the [sync engine](crate::sync)
+///
+/// Custom backends can report their own failures as [`Error::Storage`].
+pub trait Store {
+    /// Record a snapshot of a profile taken at `fetched_at`
+    fn save_profile(
+        &mut self,
+        profile: &ProfileResponse,
+        fetched_at: DateTime<Utc>,
+    ) -> Result<(), Error>;
+
+    /// Insert or replace a match including the stats of all participants
+    fn save_match(&mut self, details: &MatchDetailsResponse) -> Result<(), Error>;
+
+    /// Whether a match is stored
+    fn has_match(&self, match_id: &str) -> Result<bool, Error>;
+
+    /// Get the sync position of a player
+    fn checkpoint(&self, steam64_id: &str) -> Result<Option<SyncCheckpoint>, Error>;
+
+    /// Store the sync position of a player
+    fn save_checkpoint(
+        &mut self,
+        steam64_id: &str,
+        checkpoint: &SyncCheckpoint,
+    ) -> Result<(), Error>;
+}
+
+/// High-water mark of a player's synced match history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncCheckpoint {
+    /// ID of the newest synced match
+    pub last_match_id: String,
+    /// Finish time of the newest synced match
+    pub last_finished_at: DateTime<Utc>,
+}
+
+/// A profile as it was at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSnapshot {
+    pub fetched_at: DateTime<Utc>,
+    pub profile: ProfileResponse,
+}
+
+/// Totals of a player's matches on one map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapAggregate {
+    pub map_name: String,
+    pub matches: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+    pub kills: u32,
+    pub deaths: u32,
+    pub damage: u32,
+    pub rounds: u32,
+    pub avg_leetify_rating: Option<f64>,
+}
+
+impl MapAggregate {
+    /// Fraction of matches won (0.0 - 1.0)
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.matches.max(1) as f64
+    }
+
+    pub fn kd_ratio(&self) -> f64 {
+        self.kills as f64 / self.deaths.max(1) as f64
+    }
+
+    /// Average damage per round
+    pub fn adr(&self) -> f64 {
+        self.damage as f64 / self.rounds.max(1) as f64
+    }
+}
@@ -0,0 +1,605 @@
+use super::{MapAggregate, ProfileSnapshot, Store, SyncCheckpoint};
+use crate::error::Error;
+use crate::types::{MatchDetailsResponse, PlayerStats, ProfileResponse, TeamScore};
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::Path;
+
+/// Schema migrations, applied in order; `PRAGMA user_version` records how
+/// many have been applied
+const MIGRATIONS: &[&str] = &[
+    r#"
+CREATE TABLE profile_snapshots (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    steam64_id      TEXT NOT NULL,
+    leetify_id      TEXT,
+    name            TEXT NOT NULL,
+    fetched_at      TEXT NOT NULL,
+    winrate         REAL NOT NULL,
+    total_matches   INTEGER NOT NULL,
+    premier         INTEGER,
+    faceit          INTEGER,
+    faceit_elo      INTEGER,
+    leetify         REAL,
+    aim             REAL NOT NULL,
+    positioning     REAL NOT NULL,
+    utility         REAL NOT NULL,
+    clutch          REAL NOT NULL,
+    opening         REAL NOT NULL,
+    ct_leetify      REAL NOT NULL,
+    t_leetify       REAL NOT NULL,
+    banned          INTEGER NOT NULL,
+    data            TEXT NOT NULL
+);
+CREATE INDEX profile_snapshots_player ON profile_snapshots (steam64_id, fetched_at);
+
+CREATE TABLE matches (
+    id                   TEXT PRIMARY KEY,
+    finished_at          TEXT NOT NULL,
+    data_source          TEXT NOT NULL,
+    data_source_match_id TEXT NOT NULL,
+    map_name             TEXT NOT NULL,
+    has_banned_player    INTEGER NOT NULL,
+    team1_number         INTEGER NOT NULL,
+    team1_score          INTEGER NOT NULL,
+    team2_number         INTEGER NOT NULL,
+    team2_score          INTEGER NOT NULL
+);
+CREATE INDEX matches_finished_at ON matches (finished_at);
+
+CREATE TABLE player_stats (
+    match_id        TEXT NOT NULL REFERENCES matches (id) ON DELETE CASCADE,
+    steam64_id      TEXT NOT NULL,
+    name            TEXT NOT NULL,
+    team_number     INTEGER NOT NULL,
+    total_kills     INTEGER NOT NULL,
+    total_deaths    INTEGER NOT NULL,
+    total_assists   INTEGER NOT NULL,
+    total_damage    INTEGER NOT NULL,
+    total_hs_kills  INTEGER NOT NULL,
+    mvps            INTEGER NOT NULL,
+    score           INTEGER NOT NULL,
+    rounds_count    INTEGER NOT NULL,
+    rounds_won      INTEGER NOT NULL,
+    rounds_lost     INTEGER NOT NULL,
+    leetify_rating  REAL,
+    data            TEXT NOT NULL,
+    PRIMARY KEY (match_id, steam64_id)
+);
+CREATE INDEX player_stats_player ON player_stats (steam64_id);
+"#,
+    r#"
+CREATE TABLE sync_checkpoints (
+    steam64_id       TEXT PRIMARY KEY,
+    last_match_id    TEXT NOT NULL,
+    last_finished_at TEXT NOT NULL
+);
+"#,
+];
+
+/// SQLite-backed store for profile snapshots and match details
+///
+/// Matches are stored in a `matches` table with one `player_stats` row per
+/// participant; profiles are stored as timestamped snapshots. Frequently
+/// queried values have their own columns, and every row keeps the full JSON
+/// in a `data` column so nothing is lost.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::{Client, Id};
+/// # use leetify::store::SqliteStore;
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let client = Client::new();
+/// let store = SqliteStore::open("leetify.db")?;
+///
+/// let id = Id::Steam64("76561198283431555".into());
+/// store.save_profile(&client.get_profile(id.clone()).await?, chrono::Utc::now())?;
+/// for details in client.get_profile_matches(id).await? {
+///     store.save_match(&details)?;
+/// }
+///
+/// for map in store.map_aggregates("76561198283431555", None, None)? {
+///     println!("{}: {:.0}% win rate", map.map_name, map.win_rate() * 100.0);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// Open (or create) a database file and apply pending migrations
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Open a temporary in-memory database
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Use an existing connection, applying pending migrations
+    pub fn from_connection(mut conn: Connection) -> Result<Self, Error> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut conn)?;
+        Ok(Self { conn })
+    }
+
+    /// Get the underlying connection, e.g. for custom queries
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Record a snapshot of a profile taken at `fetched_at`
+    pub fn save_profile(
+        &self,
+        profile: &ProfileResponse,
+        fetched_at: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        let rating = &profile.rating;
+        self.conn.execute(
+            "INSERT INTO profile_snapshots (
+                steam64_id, leetify_id, name, fetched_at, winrate, total_matches,
+                premier, faceit, faceit_elo, leetify,
+                aim, positioning, utility, clutch, opening, ct_leetify, t_leetify,
+                banned, data
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
+            params![
+                profile.steam64_id,
+                profile.id,
+                profile.name,
+                fetched_at,
+                profile.winrate,
+                profile.total_matches,
+                profile.ranks.premier,
+                profile.ranks.faceit,
+                profile.ranks.faceit_elo,
+                profile.ranks.leetify,
+                rating.aim,
+                rating.positioning,
+                rating.utility,
+                rating.clutch,
+                rating.opening,
+                rating.ct_leetify,
+                rating.t_leetify,
+                !profile.bans.is_empty(),
+                serde_json::to_string(profile)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get the most recent snapshot of a player's profile
+    pub fn latest_profile(&self, steam64_id: &str) -> Result<Option<ProfileSnapshot>, Error> {
+        self.conn
+            .query_row(
+                "SELECT fetched_at, data FROM profile_snapshots
+                 WHERE steam64_id = ?1 ORDER BY fetched_at DESC, id DESC LIMIT 1",
+                params![steam64_id],
+                |row| Ok((row.get(0)?, row.get::<_, String>(1)?)),
+            )
+            .optional()?
+            .map(|(fetched_at, data)| snapshot(fetched_at, &data))
+            .transpose()
+    }
+
+    /// Get all snapshots of a player's profile in a time range, oldest first
+    pub fn profile_history(
+        &self,
+        steam64_id: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<ProfileSnapshot>, Error> {
+        let mut statement = self.conn.prepare(
+            "SELECT fetched_at, data FROM profile_snapshots
+             WHERE steam64_id = ?1
+               AND (?2 IS NULL OR fetched_at >= ?2)
+               AND (?3 IS NULL OR fetched_at < ?3)
+             ORDER BY fetched_at, id",
+        )?;
+        let rows = statement.query_map(params![steam64_id, from, to], |row| {
+            Ok((row.get(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut snapshots = Vec::new();
+        for row in rows {
+            let (fetched_at, data) = row?;
+            snapshots.push(snapshot(fetched_at, &data)?);
+        }
+        Ok(snapshots)
+    }
+
+    /// Insert or replace a match and the stats of all its participants
+    pub fn save_match(&self, details: &MatchDetailsResponse) -> Result<(), Error> {
+        let tx = self.conn.unchecked_transaction()?;
+        let [team1, team2] = &details.team_scores;
+
+        tx.execute(
+            "INSERT INTO matches (
+                id, finished_at, data_source, data_source_match_id, map_name, has_banned_player,
+                team1_number, team1_score, team2_number, team2_score
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            ON CONFLICT (id) DO UPDATE SET
+                finished_at = excluded.finished_at,
+                data_source = excluded.data_source,
+                data_source_match_id = excluded.data_source_match_id,
+                map_name = excluded.map_name,
+                has_banned_player = excluded.has_banned_player,
+                team1_number = excluded.team1_number,
+                team1_score = excluded.team1_score,
+                team2_number = excluded.team2_number,
+                team2_score = excluded.team2_score",
+            params![
+                details.id,
+                details.finished_at,
+                details.data_source,
+                details.data_source_match_id,
+                details.map_name,
+                details.has_banned_player,
+                team1.team_number,
+                team1.score,
+                team2.team_number,
+                team2.score,
+            ],
+        )?;
+
+        tx.execute(
+            "DELETE FROM player_stats WHERE match_id = ?1",
+            params![details.id],
+        )?;
+        for stats in &details.stats {
+            tx.execute(
+                "INSERT INTO player_stats (
+                    match_id, steam64_id, name, team_number, total_kills, total_deaths,
+                    total_assists, total_damage, total_hs_kills, mvps, score,
+                    rounds_count, rounds_won, rounds_lost, leetify_rating, data
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+                params![
+                    details.id,
+                    stats.steam64_id,
+                    stats.name,
+                    stats.initial_team_number,
+                    stats.total_kills,
+                    stats.total_deaths,
+                    stats.total_assists,
+                    stats.total_damage,
+                    stats.total_hs_kills,
+                    stats.mvps,
+                    stats.score,
+                    stats.rounds_count,
+                    stats.rounds_won,
+                    stats.rounds_lost,
+                    stats.leetify_rating,
+                    serde_json::to_string(stats)?,
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Whether a match is stored
+    pub fn has_match(&self, match_id: &str) -> Result<bool, Error> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT 1 FROM matches WHERE id = ?1",
+                params![match_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some())
+    }
+
+    /// Get a stored match by its Leetify match ID
+    pub fn get_match(&self, match_id: &str) -> Result<Option<MatchDetailsResponse>, Error> {
+        let details = self
+            .conn
+            .query_row(
+                "SELECT id, finished_at, data_source, data_source_match_id, map_name,
+                        has_banned_player, team1_number, team1_score, team2_number, team2_score
+                 FROM matches WHERE id = ?1",
+                params![match_id],
+                match_row,
+            )
+            .optional()?;
+
+        details.map(|d| self.with_stats(d)).transpose()
+    }
+
+    /// Get the stored matches a player took part in within a time range,
+    /// most recent first
+    pub fn matches_for_player(
+        &self,
+        steam64_id: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<MatchDetailsResponse>, Error> {
+        let mut statement = self.conn.prepare(
+            "SELECT m.id, m.finished_at, m.data_source, m.data_source_match_id, m.map_name,
+                    m.has_banned_player, m.team1_number, m.team1_score, m.team2_number, m.team2_score
+             FROM matches m JOIN player_stats p ON p.match_id = m.id
+             WHERE p.steam64_id = ?1
+               AND (?2 IS NULL OR m.finished_at >= ?2)
+               AND (?3 IS NULL OR m.finished_at < ?3)
+             ORDER BY m.finished_at DESC",
+        )?;
+        let rows = statement.query_map(params![steam64_id, from, to], match_row)?;
+
+        let mut matches = Vec::new();
+        for row in rows {
+            matches.push(self.with_stats(row?)?);
+        }
+        Ok(matches)
+    }
+
+    /// Get a player's totals per map within a time range, most played first
+    pub fn map_aggregates(
+        &self,
+        steam64_id: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<MapAggregate>, Error> {
+        let mut statement = self.conn.prepare(
+            "SELECT m.map_name,
+                    COUNT(*),
+                    SUM(p.rounds_won > p.rounds_lost),
+                    SUM(p.rounds_won < p.rounds_lost),
+                    SUM(p.rounds_won = p.rounds_lost),
+                    SUM(p.total_kills),
+                    SUM(p.total_deaths),
+                    SUM(p.total_damage),
+                    SUM(p.rounds_count),
+                    AVG(p.leetify_rating)
+             FROM matches m JOIN player_stats p ON p.match_id = m.id
+             WHERE p.steam64_id = ?1
+               AND (?2 IS NULL OR m.finished_at >= ?2)
+               AND (?3 IS NULL OR m.finished_at < ?3)
+             GROUP BY m.map_name
+             ORDER BY COUNT(*) DESC, m.map_name",
+        )?;
+        let rows = statement.query_map(params![steam64_id, from, to], |row| {
+            Ok(MapAggregate {
+                map_name: row.get(0)?,
+                matches: row.get(1)?,
+                wins: row.get(2)?,
+                losses: row.get(3)?,
+                ties: row.get(4)?,
+                kills: row.get(5)?,
+                deaths: row.get(6)?,
+                damage: row.get(7)?,
+                rounds: row.get(8)?,
+                avg_leetify_rating: row.get(9)?,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn with_stats(&self, mut details: MatchDetailsResponse) -> Result<MatchDetailsResponse, Error> {
+        let mut statement = self
+            .conn
+            .prepare_cached("SELECT data FROM player_stats WHERE match_id = ?1 ORDER BY rowid")?;
+        let rows = statement.query_map(params![details.id], |row| row.get::<_, String>(0))?;
+
+        for data in rows {
+            details
+                .stats
+                .push(serde_json::from_str::<PlayerStats>(&data?)?);
+        }
+        Ok(details)
+    }
+}
+
+impl Store for SqliteStore {
+    fn save_profile(
+        &mut self,
+        profile: &ProfileResponse,
+        fetched_at: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        SqliteStore::save_profile(self, profile, fetched_at)
+    }
+
+    fn save_match(&mut self, details: &MatchDetailsResponse) -> Result<(), Error> {
+        SqliteStore::save_match(self, details)
+    }
+
+    fn has_match(&self, match_id: &str) -> Result<bool, Error> {
+        SqliteStore::has_match(self, match_id)
+    }
+
+    fn checkpoint(&self, steam64_id: &str) -> Result<Option<SyncCheckpoint>, Error> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT last_match_id, last_finished_at FROM sync_checkpoints
+                 WHERE steam64_id = ?1",
+                params![steam64_id],
+                |row| {
+                    Ok(SyncCheckpoint {
+                        last_match_id: row.get(0)?,
+                        last_finished_at: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    fn save_checkpoint(
+        &mut self,
+        steam64_id: &str,
+        checkpoint: &SyncCheckpoint,
+    ) -> Result<(), Error> {
+        self.conn.execute(
+            "INSERT INTO sync_checkpoints (steam64_id, last_match_id, last_finished_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT (steam64_id) DO UPDATE SET
+                last_match_id = excluded.last_match_id,
+                last_finished_at = excluded.last_finished_at",
+            params![
+                steam64_id,
+                checkpoint.last_match_id,
+                checkpoint.last_finished_at
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+fn migrate(conn: &mut Connection) -> Result<(), Error> {
+    let version: u32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+    let tx = conn.transaction()?;
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", i as u32 + 1)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+fn snapshot(fetched_at: DateTime<Utc>, data: &str) -> Result<ProfileSnapshot, Error> {
+    Ok(ProfileSnapshot {
+        fetched_at,
+        profile: serde_json::from_str(data)?,
+    })
+}
+
+fn match_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<MatchDetailsResponse> {
+    Ok(MatchDetailsResponse {
+        id: row.get(0)?,
+        finished_at: row.get(1)?,
+        data_source: row.get(2)?,
+        data_source_match_id: row.get(3)?,
+        map_name: row.get(4)?,
+        has_banned_player: row.get(5)?,
+        team_scores: [
+            TeamScore {
+                team_number: row.get(6)?,
+                score: row.get(7)?,
+            },
+            TeamScore {
+                team_number: row.get(8)?,
+                score: row.get(9)?,
+            },
+        ],
+        stats: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(day * 86_400, 0).unwrap()
+    }
+
+    fn game(id: &str, map: &str, day: i64, won: bool) -> MatchDetailsResponse {
+        let line = |steam64_id: &str, won: bool| PlayerStats {
+            steam64_id: steam64_id.to_string(),
+            total_kills: 20,
+            total_deaths: 10,
+            total_damage: 2000,
+            rounds_count: 20,
+            rounds_won: if won { 13 } else { 7 },
+            rounds_lost: if won { 7 } else { 13 },
+            leetify_rating: Some(0.1),
+            ..Default::default()
+        };
+        MatchDetailsResponse {
+            id: id.to_string(),
+            finished_at: at(day),
+            map_name: map.to_string(),
+            stats: vec![line("me", won), line("other", !won)],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_migrations_are_idempotent() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let mut conn = store.conn;
+        migrate(&mut conn).unwrap();
+        let version: u32 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version as usize, MIGRATIONS.len());
+    }
+
+    #[test]
+    fn test_profile_snapshots() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let profile = |name: &str| ProfileResponse {
+            steam64_id: "me".to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        };
+
+        assert!(store.latest_profile("me").unwrap().is_none());
+        store.save_profile(&profile("old"), at(1)).unwrap();
+        store.save_profile(&profile("new"), at(2)).unwrap();
+
+        let latest = store.latest_profile("me").unwrap().unwrap();
+        assert_eq!(latest.profile.name, "new");
+        assert_eq!(latest.fetched_at, at(2));
+        assert_eq!(store.profile_history("me", None, None).unwrap().len(), 2);
+        assert_eq!(
+            store
+                .profile_history("me", Some(at(2)), None)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_matches() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        store.save_match(&game("m1", "de_nuke", 1, true)).unwrap();
+        store.save_match(&game("m2", "de_nuke", 2, false)).unwrap();
+        store
+            .save_match(&game("m3", "de_ancient", 3, true))
+            .unwrap();
+        // Upserting replaces the participants instead of duplicating them
+        store
+            .save_match(&game("m3", "de_ancient", 3, true))
+            .unwrap();
+
+        assert!(store.has_match("m1").unwrap());
+        assert!(!store.has_match("m4").unwrap());
+
+        let m3 = store.get_match("m3").unwrap().unwrap();
+        assert_eq!(m3.stats.len(), 2);
+        assert_eq!(m3.finished_at, at(3));
+
+        let matches = store.matches_for_player("me", Some(at(2)), None).unwrap();
+        let ids: Vec<_> = matches.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["m3", "m2"]);
+
+        let maps = store.map_aggregates("me", None, None).unwrap();
+        assert_eq!(maps[0].map_name, "de_nuke");
+        assert_eq!((maps[0].matches, maps[0].wins, maps[0].losses), (2, 1, 1));
+        assert_eq!(maps[0].kd_ratio(), 2.0);
+        assert_eq!(maps[0].adr(), 100.0);
+        assert_eq!(maps[1].avg_leetify_rating, Some(0.1));
+    }
+
+    #[test]
+    fn test_checkpoints() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        assert!(store.checkpoint("me").unwrap().is_none());
+
+        for (id, day) in [("m1", 1), ("m2", 2)] {
+            let checkpoint = SyncCheckpoint {
+                last_match_id: id.to_string(),
+                last_finished_at: at(day),
+            };
+            store.save_checkpoint("me", &checkpoint).unwrap();
+            assert_eq!(store.checkpoint("me").unwrap(), Some(checkpoint));
+        }
+    }
+}
@@ -0,0 +1,352 @@
+//! Incremental sync of tracked players into a [`Store`]
+
+use crate::client::Client;
+use crate::error::Error;
+use crate::store::{Store, SyncCheckpoint};
+use crate::types::{Id, MatchDetailsResponse};
+use chrono::Utc;
+use std::collections::HashSet;
+
+/// Progress of a running [`SyncJob`]
+#[derive(Debug, Clone)]
+pub enum SyncProgress {
+    /// Syncing of a player started
+    PlayerStarted {
+        player: Id,
+        /// Position of the player in the job, starting at 0
+        index: usize,
+        total: usize,
+    },
+    /// A new match was stored
+    MatchSaved {
+        player: Id,
+        match_id: String,
+        /// Whether the match details had to be fetched separately
+        hydrated: bool,
+    },
+    /// Syncing of a player finished, possibly with failures
+    PlayerFinished {
+        player: Id,
+        new_matches: usize,
+        failures: usize,
+    },
+}
+
+/// Something that went wrong while syncing a player
+#[derive(Debug)]
+pub struct SyncFailure {
+    /// The match that could not be synced, or `None` if the whole player failed
+    pub match_id: Option<String>,
+    pub error: Error,
+}
+
+/// Outcome of syncing one player
+#[derive(Debug)]
+pub struct PlayerSync {
+    pub player: Id,
+    /// Steam64 ID the player's data is stored under, once known
+    pub steam64_id: Option<String>,
+    /// IDs of the matches stored by this run, oldest first
+    pub new_matches: Vec<String>,
+    /// Number of new matches whose details were fetched separately
+    pub hydrated: usize,
+    /// High-water mark after this run
+    pub checkpoint: Option<SyncCheckpoint>,
+    pub failures: Vec<SyncFailure>,
+}
+
+impl PlayerSync {
+    fn new(player: Id) -> Self {
+        Self {
+            player,
+            steam64_id: None,
+            new_matches: Vec::new(),
+            hydrated: 0,
+            checkpoint: None,
+            failures: Vec::new(),
+        }
+    }
+}
+
+/// Outcome of a [`SyncJob`]
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub players: Vec<PlayerSync>,
+}
+
+impl SyncReport {
+    /// Total number of matches stored by this run
+    pub fn new_matches(&self) -> usize {
+        self.players.iter().map(|p| p.new_matches.len()).sum()
+    }
+
+    /// All failures with the player they belong to
+    pub fn failures(&self) -> impl Iterator<Item = (&Id, &SyncFailure)> {
+        self.players
+            .iter()
+            .flat_map(|p| p.failures.iter().map(move |f| (&p.player, f)))
+    }
+
+    /// Whether every player synced without failures
+    pub fn is_success(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
+
+type ProgressCallback<'a> = Box<dyn FnMut(&SyncProgress) + Send + 'a>;
+
+/// Job that brings a store up to date with the match histories of a set of
+/// tracked players
+///
+/// For every player the job records a profile snapshot, fetches the match
+/// history, and stores the matches that are not stored yet, fetching full
+/// details for matches that come without stats. Matches are compared by ID,
+/// not by time, so matches that Leetify processes late are stored too. An
+/// interrupted run continues where it stopped, and matches that fail are
+/// retried by the next run. The player's [`SyncCheckpoint`] tracks the newest
+/// stored match.
+///
+/// Failures never abort the job; they are collected in the [`SyncReport`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use leetify::Client;
+/// # use leetify::store::MemoryStore;
+/// # use leetify::sync::{SyncJob, SyncProgress};
+/// # async fn example() -> Result<(), leetify::Error> {
+/// let client = Client::new();
+/// let mut store = MemoryStore::new();
+///
+/// let report = SyncJob::new(&client, &mut store)
+///     .players(["76561198283431555", "76561197960287930"])
+///     .on_progress(|progress| {
+///         if let SyncProgress::MatchSaved { match_id, .. } = progress {
+///             println!("Stored {}", match_id);
+///         }
+///     })
+///     .run()
+///     .await;
+///
+/// println!("{} new matches", report.new_matches());
+/// for (player, failure) in report.failures() {
+///     eprintln!("{:?}: {}", player, failure.error);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct SyncJob<'a, S: Store> {
+    client: Client,
+    store: &'a mut S,
+    players: Vec<Id>,
+    on_progress: Option<ProgressCallback<'a>>,
+}
+
+impl<'a, S: Store> SyncJob<'a, S> {
+    pub fn new(client: &Client, store: &'a mut S) -> Self {
+        Self {
+            client: client.clone(),
+            store,
+            players: Vec::new(),
+            on_progress: None,
+        }
+    }
+
+    /// Track a player
+    pub fn player(mut self, id: impl Into<Id>) -> Self {
+        self.players.push(id.into());
+        self
+    }
+
+    /// Track several players
+    pub fn players<I>(mut self, ids: impl IntoIterator<Item = I>) -> Self
+    where
+        I: Into<Id>,
+    {
+        self.players.extend(ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Call `f` for every progress event
+    pub fn on_progress(mut self, f: impl FnMut(&SyncProgress) + Send + 'a) -> Self {
+        self.on_progress = Some(Box::new(f));
+        self
+    }
+
+    /// Sync all players one after another
+    pub async fn run(mut self) -> SyncReport {
+        let players = std::mem::take(&mut self.players);
+        let total = players.len();
+        let mut report = SyncReport::default();
+
+        for (index, player) in players.into_iter().enumerate() {
+            self.emit(SyncProgress::PlayerStarted {
+                player: player.clone(),
+                index,
+                total,
+            });
+
+            let mut result = PlayerSync::new(player.clone());
+            if let Err(error) = self.sync_player(&player, &mut result).await {
+                result.failures.push(SyncFailure {
+                    match_id: None,
+                    error,
+                });
+            }
+
+            self.emit(SyncProgress::PlayerFinished {
+                player,
+                new_matches: result.new_matches.len(),
+                failures: result.failures.len(),
+            });
+            report.players.push(result);
+        }
+
+        report
+    }
+
+    async fn sync_player(&mut self, player: &Id, result: &mut PlayerSync) -> Result<(), Error> {
+        let profile = self.client.get_profile(player.clone()).await?;
+        let steam64_id = profile.steam64_id.clone();
+        result.steam64_id = Some(steam64_id.clone());
+        self.store.save_profile(&profile, Utc::now())?;
+
+        result.checkpoint = self.store.checkpoint(&steam64_id)?;
+        let history = self.client.get_profile_matches(player.clone()).await?;
+        let pending = pending_matches(history, &*self.store)?;
+
+        // Failed matches are not stored, so the next run retries them
+        for mut details in pending {
+            let hydrated = details.stats.is_empty();
+            if hydrated {
+                match self.client.get_match_by_game_id(details.id.clone()).await {
+                    Ok(full) => details = full,
+                    Err(error) => {
+                        result.failures.push(SyncFailure {
+                            match_id: Some(details.id),
+                            error,
+                        });
+                        continue;
+                    }
+                }
+            }
+
+            self.store.save_match(&details)?;
+            let newest = result
+                .checkpoint
+                .as_ref()
+                .is_none_or(|c| details.finished_at >= c.last_finished_at);
+            if newest {
+                let checkpoint = SyncCheckpoint {
+                    last_match_id: details.id.clone(),
+                    last_finished_at: details.finished_at,
+                };
+                self.store.save_checkpoint(&steam64_id, &checkpoint)?;
+                result.checkpoint = Some(checkpoint);
+            }
+
+            if hydrated {
+                result.hydrated += 1;
+            }
+            result.new_matches.push(details.id.clone());
+            self.emit(SyncProgress::MatchSaved {
+                player: player.clone(),
+                match_id: details.id,
+                hydrated,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn emit(&mut self, progress: SyncProgress) {
+        if let Some(on_progress) = &mut self.on_progress {
+            on_progress(&progress);
+        }
+    }
+}
+
+/// Matches from a history that are not stored yet, oldest first
+fn pending_matches<S: Store>(
+    history: Vec<MatchDetailsResponse>,
+    store: &S,
+) -> Result<Vec<MatchDetailsResponse>, Error> {
+    let mut ids = HashSet::new();
+    let mut pending = Vec::new();
+    for details in history {
+        if ids.insert(details.id.clone()) && !store.has_match(&details.id)? {
+            pending.push(details);
+        }
+    }
+    pending.sort_by_key(|d| d.finished_at);
+    Ok(pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+    use crate::types::PlayerStats;
+    use chrono::DateTime;
+
+    fn game(id: &str, day: i64) -> MatchDetailsResponse {
+        MatchDetailsResponse {
+            id: id.to_string(),
+            finished_at: DateTime::from_timestamp(day * 86_400, 0).unwrap(),
+            stats: vec![PlayerStats::default()],
+            ..Default::default()
+        }
+    }
+
+    fn ids(matches: &[MatchDetailsResponse]) -> Vec<&str> {
+        matches.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_pending_matches() {
+        let mut store = MemoryStore::new();
+        let history = || vec![game("c", 3), game("b", 2), game("a", 1), game("c", 3)];
+
+        let pending = pending_matches(history(), &store).unwrap();
+        assert_eq!(ids(&pending), vec!["a", "b", "c"]);
+
+        store.save_match(&game("b", 2)).unwrap();
+        let pending = pending_matches(history(), &store).unwrap();
+        assert_eq!(ids(&pending), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn test_late_matches_are_pending() {
+        let mut store = MemoryStore::new();
+        for details in [game("b", 2), game("c", 3)] {
+            store.save_match(&details).unwrap();
+        }
+
+        // "a" finished before the newest stored match but showed up later
+        let history = vec![game("c", 3), game("b", 2), game("a", 1)];
+        let pending = pending_matches(history, &store).unwrap();
+        assert_eq!(ids(&pending), vec!["a"]);
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_reported() {
+        let client = Client::builder()
+            .base_url("http://127.0.0.1:1")
+            .build()
+            .unwrap();
+        let mut store = MemoryStore::new();
+        let mut events = Vec::new();
+
+        let report = SyncJob::new(&client, &mut store)
+            .players(["76561198283431555", "76561197960287930"])
+            .on_progress(|progress| events.push(progress.clone()))
+            .run()
+            .await;
+
+        assert_eq!(report.players.len(), 2);
+        assert_eq!(report.failures().count(), 2);
+        assert!(!report.is_success());
+        assert!(report.failures().all(|(_, f)| f.match_id.is_none()));
+        assert_eq!(events.len(), 4);
+    }
+}
@@ -103,13 +103,24 @@ impl From<String> for Id {
 }
 
 /// Data source for matches
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+///
+/// Serialized as the API's lowercase name (see [`DataSource::as_str`]).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DataSource {
     FACEIT,
     Matchmaking,
     Other(String),
 }
 
+impl Serialize for DataSource {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
 impl<'de> serde::Deserialize<'de> for DataSource {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -154,7 +165,7 @@ impl From<&str> for DataSource {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProfileResponse {
     pub privacy_mode: String,
     pub winrate: f64,
@@ -173,7 +184,7 @@ pub struct ProfileResponse {
     pub recent_teammates: Vec<RecentTeammate>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Ranks {
     #[serde(default)]
     pub leetify: Option<f64>,
@@ -190,13 +201,13 @@ pub struct Ranks {
     pub competitive: Vec<CompetitiveRank>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CompetitiveRank {
     pub map_name: String,
     pub rank: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Rating {
     pub aim: f64,
     pub positioning: f64,
@@ -207,7 +218,7 @@ pub struct Rating {
     pub t_leetify: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Stats {
     pub accuracy_enemy_spotted: f64,
     pub accuracy_head: f64,
@@ -232,7 +243,7 @@ pub struct Stats {
     pub utility_on_death_avg: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RecentMatch {
     pub id: String,
     pub finished_at: DateTime<Utc>,
@@ -252,20 +263,20 @@ pub struct RecentMatch {
     pub spray_accuracy: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RecentTeammate {
     pub steam64_id: String,
     pub recent_matches_count: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct PlatformBanInfo {
     pub platform: String,
     pub platform_nickname: String,
     pub banned_since: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MatchDetailsResponse {
     pub id: String,
     pub finished_at: DateTime<Utc>,
@@ -278,13 +289,20 @@ pub struct MatchDetailsResponse {
     pub stats: Vec<PlayerStats>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl MatchDetailsResponse {
+    /// Get the stats line of a participant by Steam64 ID
+    pub fn player_stats(&self, steam64_id: &str) -> Option<&PlayerStats> {
+        self.stats.iter().find(|s| s.steam64_id == steam64_id)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TeamScore {
     pub team_number: u32,
     pub score: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PlayerStats {
     pub steam64_id: String,
     pub name: String,
@@ -393,3 +411,71 @@ where
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_data_source_serde() {
+        for (source, name) in [
+            (DataSource::FACEIT, "faceit"),
+            (DataSource::Matchmaking, "matchmaking"),
+            (DataSource::Other("renown".to_string()), "renown"),
+        ] {
+            assert_eq!(serde_json::to_value(&source).unwrap(), json!(name));
+            assert_eq!(
+                serde_json::from_value::<DataSource>(json!(name)).unwrap(),
+                source
+            );
+        }
+        assert!(serde_json::from_value::<DataSource>(json!(1)).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_score() {
+        let recent_match = |score| {
+            let mut value = serde_json::to_value(RecentMatch::default()).unwrap();
+            value["score"] = score;
+            serde_json::from_value::<RecentMatch>(value)
+        };
+
+        assert_eq!(recent_match(json!([13, 9])).unwrap().score, [13, 9]);
+        for score in [json!([]), json!([13]), json!([13, 9, 1])] {
+            let error = recent_match(score).unwrap_err().to_string();
+            assert!(error.contains("exactly 2 elements"), "{}", error);
+        }
+        assert!(recent_match(json!([13, -1])).is_err());
+        assert!(recent_match(json!("13:9")).is_err());
+        assert!(recent_match(json!(null)).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_team_scores() {
+        let details = |team_scores| {
+            let mut value = serde_json::to_value(MatchDetailsResponse::default()).unwrap();
+            value["team_scores"] = team_scores;
+            serde_json::from_value::<MatchDetailsResponse>(value)
+        };
+        let team = |team_number, score| json!({"team_number": team_number, "score": score});
+
+        let parsed = details(json!([team(3, 9), team(2, 13)])).unwrap();
+        assert_eq!(
+            parsed
+                .team_scores
+                .map(|team| (team.team_number, team.score)),
+            [(3, 9), (2, 13)]
+        );
+        for team_scores in [
+            json!([]),
+            json!([team(2, 13)]),
+            json!([team(2, 13), team(3, 9), team(1, 0)]),
+        ] {
+            let error = details(team_scores).unwrap_err().to_string();
+            assert!(error.contains("exactly 2 elements"), "{}", error);
+        }
+        assert!(details(json!([team(2, 13), {"team_number": 3}])).is_err());
+        assert!(details(json!({"2": 13, "3": 9})).is_err());
+    }
+}
@@ -0,0 +1,523 @@
+//! Record-and-replay ("VCR") mode for API interactions
+//!
+//! A [`Vcr`] attached to a [`Client`](crate::Client) with
+//! [`ClientBuilder::vcr`](crate::ClientBuilder::vcr) stores every request
+//! and response in a JSON cassette file, and answers later requests from
+//! that cassette instead of the API. This allows recording real responses
+//! once and replaying them deterministically, e.g. in CI.
+//!
+//! Requests are matched on method, path and query. The API key is never
+//! written to a cassette: its header value is replaced by a placeholder.
+//! Response bodies are recorded as they are.
+//!
+//! New interactions are kept in memory and written by [`Vcr::save`], or when
+//! the last clone of the VCR is dropped.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), leetify::Error> {
+//! use leetify::Client;
+//! use leetify::vcr::Vcr;
+//!
+//! // Records on the first run, replays afterwards
+//! let client = Client::builder()
+//!     .api_key("your-api-key")
+//!     .vcr(Vcr::replay("tests/cassettes/profile.json")?)
+//!     .build()?;
+//! let profile = client.get_profile("76561198283431555").await?;
+//! drop(client); // writes the cassette if something new was recorded
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::{API_KEY_HEADER, Reply};
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Placeholder written to cassettes instead of the API key
+pub const SCRUBBED: &str = "[scrubbed]";
+
+/// How a [`Vcr`] uses its cassette
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Send every request to the API and record it, replacing the cassette
+    Record,
+    /// Replay recorded requests; send and record the ones not in the
+    /// cassette yet
+    Replay,
+    /// Replay recorded requests; fail with [`Error::Vcr`] on any request
+    /// not in the cassette
+    Strict,
+}
+
+/// Recorded API interactions, as stored in a cassette file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Read a cassette from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    /// Write the cassette to a JSON file, creating parent directories
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+/// A request and the response the API gave to it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// The parts of a request that are recorded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    /// Query string without the leading `?`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Request headers, with the API key replaced by [`SCRUBBED`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl RecordedRequest {
+    fn new(request: &reqwest::Request) -> Self {
+        let headers = request
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                let value = if name == API_KEY_HEADER {
+                    SCRUBBED.to_string()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                (name.to_string(), value)
+            })
+            .collect();
+        Self {
+            method: request.method().to_string(),
+            path: request.url().path().to_string(),
+            query: request.url().query().map(str::to_string),
+            headers,
+        }
+    }
+
+    /// Whether both requests have the same method, path and query
+    pub fn matches(&self, other: &RecordedRequest) -> bool {
+        self.method == other.method && self.path == other.path && self.query == other.query
+    }
+}
+
+impl fmt::Display for RecordedRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        Ok(())
+    }
+}
+
+/// The parts of a response that are recorded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    /// `Retry-After` delay in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<u64>,
+    pub body: String,
+}
+
+impl RecordedResponse {
+    fn reply(&self) -> Result<Reply, Error> {
+        let status = reqwest::StatusCode::from_u16(self.status)
+            .map_err(|_| Error::Vcr(format!("invalid recorded status {}", self.status)))?;
+        Ok(Reply {
+            status,
+            retry_after: self.retry_after.map(Duration::from_secs),
+            body: self.body.clone(),
+        })
+    }
+}
+
+/// Records API interactions to, or replays them from, a cassette file
+///
+/// Cheap to clone; clones share the cassette, so a test can keep one to
+/// inspect what was played after handing another to the client.
+///
+/// Identical requests are answered by their recorded responses in order;
+/// once all of them were played, the last one is repeated.
+#[derive(Debug, Clone)]
+pub struct Vcr {
+    inner: Arc<VcrInner>,
+}
+
+#[derive(Debug)]
+struct VcrInner {
+    path: PathBuf,
+    mode: Mode,
+    state: Mutex<State>,
+}
+
+impl Drop for VcrInner {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap_or_else(|e| e.into_inner());
+        if state.dirty {
+            // Errors cannot be reported from here; call `Vcr::save` to see them
+            let _ = state.cassette.save(&self.path);
+        }
+    }
+}
+
+/// The cassette and which of its interactions were played
+#[derive(Debug, Default)]
+struct State {
+    cassette: Cassette,
+    played: Vec<bool>,
+    /// Whether interactions were recorded since the cassette was saved
+    dirty: bool,
+}
+
+impl Vcr {
+    /// Create a VCR for a cassette file
+    ///
+    /// [`Mode::Replay`] starts with an empty cassette if the file does not
+    /// exist yet; [`Mode::Strict`] requires it.
+    pub fn new(path: impl Into<PathBuf>, mode: Mode) -> Result<Self, Error> {
+        let path = path.into();
+        let cassette = match mode {
+            Mode::Record => Cassette::default(),
+            Mode::Replay if !path.exists() => Cassette::default(),
+            Mode::Replay | Mode::Strict => Cassette::load(&path)?,
+        };
+        Ok(Self::with_cassette(path, mode, cassette))
+    }
+
+    /// Record every request, replacing the cassette
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self::with_cassette(path.into(), Mode::Record, Cassette::default())
+    }
+
+    /// Replay recorded requests and record new ones
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        Self::new(path, Mode::Replay)
+    }
+
+    /// Replay recorded requests and fail on new ones
+    pub fn strict(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        Self::new(path, Mode::Strict)
+    }
+
+    fn with_cassette(path: PathBuf, mode: Mode, cassette: Cassette) -> Self {
+        Self {
+            inner: Arc::new(VcrInner {
+                path,
+                mode,
+                state: Mutex::new(State {
+                    played: vec![false; cassette.interactions.len()],
+                    cassette,
+                    dirty: false,
+                }),
+            }),
+        }
+    }
+
+    /// Path of the cassette file
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Mode of the VCR
+    pub fn mode(&self) -> Mode {
+        self.inner.mode
+    }
+
+    /// The current cassette, including interactions recorded so far
+    pub fn cassette(&self) -> Cassette {
+        self.state().cassette.clone()
+    }
+
+    /// Interactions of the cassette that have not been played yet
+    pub fn unplayed(&self) -> Vec<Interaction> {
+        let state = self.state();
+        state
+            .cassette
+            .interactions
+            .iter()
+            .zip(&state.played)
+            .filter(|(_, played)| !**played)
+            .map(|(interaction, _)| interaction.clone())
+            .collect()
+    }
+
+    /// Write the cassette if interactions were recorded since it was last
+    /// saved
+    ///
+    /// Also happens when the last clone of the VCR is dropped, but errors
+    /// are only reported here.
+    pub fn save(&self) -> Result<(), Error> {
+        let cassette = {
+            let mut state = self.state();
+            if !state.dirty {
+                return Ok(());
+            }
+            state.dirty = false;
+            state.cassette.clone()
+        };
+        cassette
+            .save(&self.inner.path)
+            .inspect_err(|_| self.state().dirty = true)
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.inner.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Answer a request from the cassette, or send and record it
+    pub(crate) async fn execute(
+        &self,
+        client: &reqwest::Client,
+        request: reqwest::RequestBuilder,
+    ) -> Result<Reply, Error> {
+        let request = request.build()?;
+        let recorded = RecordedRequest::new(&request);
+
+        if self.inner.mode != Mode::Record {
+            if let Some(response) = self.play(&recorded) {
+                return response.reply();
+            }
+            if self.inner.mode == Mode::Strict {
+                return Err(Error::Vcr(format!(
+                    "no interaction in {} matches {}",
+                    self.inner.path.display(),
+                    recorded
+                )));
+            }
+        }
+
+        let reply = Reply::read(client.execute(request).await?).await?;
+        self.append(Interaction {
+            request: recorded,
+            response: RecordedResponse {
+                status: reply.status.as_u16(),
+                retry_after: reply.retry_after.map(|delay| delay.as_secs()),
+                body: reply.body.clone(),
+            },
+        });
+        Ok(reply)
+    }
+
+    /// Find the response for a request, marking it as played
+    fn play(&self, request: &RecordedRequest) -> Option<RecordedResponse> {
+        let mut state = self.state();
+        let State {
+            cassette, played, ..
+        } = &mut *state;
+        let matching: Vec<usize> = cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, interaction)| interaction.request.matches(request))
+            .map(|(i, _)| i)
+            .collect();
+        let index = matching
+            .iter()
+            .copied()
+            .find(|&i| !played[i])
+            .or_else(|| matching.last().copied())?;
+        played[index] = true;
+        Some(cassette.interactions[index].response.clone())
+    }
+
+    /// Append an interaction, to be written by the next save
+    fn append(&self, interaction: Interaction) {
+        let mut state = self.state();
+        state.cassette.interactions.push(interaction);
+        state.played.push(true);
+        state.dirty = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+    use crate::types::ProfileResponse;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn cassette_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("leetify-vcr-{}-{}.json", std::process::id(), name))
+    }
+
+    /// Local API stand-in answering one request with `body`
+    async fn api(body: String) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_record_then_strict_replay() {
+        let path = cassette_path("record");
+        let profile = ProfileResponse::builder()
+            .name("recorded")
+            .steam64_id("76561198283431555")
+            .build();
+        let url = api(serde_json::to_string(&profile).unwrap()).await;
+
+        let vcr = Vcr::record(&path);
+        let recorder = Client::builder()
+            .base_url(url)
+            .api_key("secret-key")
+            .vcr(vcr.clone())
+            .build()
+            .unwrap();
+        let recorded = recorder.get_profile("76561198283431555").await.unwrap();
+        assert_eq!(recorded.name, "recorded");
+        assert!(!path.exists());
+        vcr.save().unwrap();
+
+        let cassette = std::fs::read_to_string(&path).unwrap();
+        assert!(!cassette.contains("secret-key"));
+        let interaction = &Cassette::load(&path).unwrap().interactions[0];
+        assert_eq!(
+            interaction.request.to_string(),
+            "GET /v3/profile?steam64_id=76561198283431555"
+        );
+        assert_eq!(interaction.request.headers[API_KEY_HEADER], SCRUBBED);
+
+        // Nothing listens on the recorded URL anymore
+        let vcr = Vcr::strict(&path).unwrap();
+        let player = Client::builder()
+            .base_url("http://127.0.0.1:9")
+            .vcr(vcr.clone())
+            .build()
+            .unwrap();
+        let replayed = player.get_profile("76561198283431555").await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(replayed.steam64_id, profile.steam64_id);
+        assert_eq!(replayed.name, "recorded");
+        assert!(vcr.unplayed().is_empty());
+    }
+
+    #[test]
+    fn test_recorded_interactions_are_saved_on_drop() {
+        let path = cassette_path("drop");
+        let vcr = Vcr::record(&path);
+        vcr.append(Interaction {
+            request: RecordedRequest {
+                method: "GET".to_string(),
+                path: "/v2/matches/m1".to_string(),
+                query: None,
+                headers: BTreeMap::new(),
+            },
+            response: RecordedResponse {
+                status: 404,
+                retry_after: None,
+                body: String::new(),
+            },
+        });
+        let clone = vcr.clone();
+        drop(vcr);
+        assert!(!path.exists());
+
+        drop(clone);
+        let cassette = Cassette::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(cassette.interactions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_strict_replay_rejects_unmatched_requests() {
+        let path = cassette_path("strict");
+        let interaction = |status: u16, body: String| Interaction {
+            request: RecordedRequest {
+                method: "GET".to_string(),
+                path: "/v3/profile".to_string(),
+                query: Some("steam64_id=76561198000000001".to_string()),
+                headers: BTreeMap::new(),
+            },
+            response: RecordedResponse {
+                status,
+                retry_after: (status == 429).then_some(7),
+                body,
+            },
+        };
+        let profile = ProfileResponse::builder()
+            .steam64_id("76561198000000001")
+            .build();
+        Cassette {
+            interactions: vec![
+                interaction(429, String::new()),
+                interaction(200, serde_json::to_string(&profile).unwrap()),
+            ],
+        }
+        .save(&path)
+        .unwrap();
+
+        let vcr = Vcr::strict(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let client = Client::builder()
+            .base_url("http://127.0.0.1:9")
+            .vcr(vcr.clone())
+            .build()
+            .unwrap();
+
+        // Recorded responses are played in order, then the last is repeated
+        let error = client.get_profile("76561198000000001").await.unwrap_err();
+        assert!(matches!(
+            error,
+            Error::RateLimited(Some(delay)) if delay == Duration::from_secs(7)
+        ));
+        for _ in 0..2 {
+            let replayed = client.get_profile("76561198000000001").await.unwrap();
+            assert_eq!(replayed.steam64_id, "76561198000000001");
+        }
+        assert!(vcr.unplayed().is_empty());
+
+        let error = client.get_profile("76561198000000002").await.unwrap_err();
+        assert!(
+            matches!(&error, Error::Vcr(message) if message.contains("steam64_id=76561198000000002"))
+        );
+    }
+}
@@ -0,0 +1,717 @@
+//! Polling watchers that turn a player's match history and profile into
+//! streams of new matches and profile changes
+
+use crate::changes::{ChangeThresholds, ProfileChange, ProfileDiff};
+use crate::client::Client;
+use crate::error::Error;
+use crate::player::Player;
+use crate::types::{Id, MatchDetailsResponse, ProfileResponse, Steam64Id};
+use chrono::{DateTime, Utc};
+use futures_util::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::{HashSet, VecDeque};
+use std::hash::BuildHasher;
+use std::time::{Duration, Instant};
+
+/// Number of emitted match IDs remembered for deduplication
+const MAX_SEEN: usize = 100;
+
+/// How far before the most recent match seen a match may have finished and
+/// still count as new, since Leetify can process matches out of order
+const LOOKBACK_DAYS: i64 = 7;
+
+/// Default upper bound for the delay between polls after failures
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(15 * 60);
+
+/// Resumable position of a watcher
+///
+/// Every emitted [`WatchedMatch`] carries the state right after it, which can
+/// be persisted and passed to [`WatchOptions::resume`] after a restart.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchState {
+    /// ID of the most recently emitted match
+    pub last_match_id: Option<String>,
+    /// Finish time of the most recent match seen
+    pub last_finished_at: Option<DateTime<Utc>>,
+    /// Recently seen match IDs, oldest first
+    #[serde(default)]
+    pub seen: VecDeque<String>,
+}
+
+impl WatchState {
+    /// Whether the watcher has polled at least once
+    pub fn is_primed(&self) -> bool {
+        self.last_finished_at.is_some()
+    }
+
+    /// Whether a match has not been seen so far
+    ///
+    /// Matches that show up late with an older finish time are new too, as
+    /// long as they finished at most a week before the most recent match
+    /// seen; older ones are assumed to have dropped out of the bounded set of
+    /// seen IDs.
+    pub fn is_new(&self, details: &MatchDetailsResponse) -> bool {
+        let recent = self
+            .last_finished_at
+            .is_none_or(|last| details.finished_at >= last - chrono::Duration::days(LOOKBACK_DAYS));
+        recent && !self.seen.contains(&details.id)
+    }
+
+    /// Mark a match as seen
+    pub fn record(&mut self, details: &MatchDetailsResponse) {
+        if !self.seen.contains(&details.id) {
+            self.seen.push_back(details.id.clone());
+            while self.seen.len() > MAX_SEEN {
+                self.seen.pop_front();
+            }
+        }
+        if self
+            .last_finished_at
+            .is_none_or(|last| details.finished_at >= last)
+        {
+            self.last_finished_at = Some(details.finished_at);
+            self.last_match_id = Some(details.id.clone());
+        }
+    }
+}
+
+/// A newly finished match of a watched player
+#[derive(Debug, Clone)]
+pub struct WatchedMatch {
+    /// The watched player
+    pub player: Id,
+    /// Steam64 ID of the watched player, resolved through their profile if
+    /// they are watched by Leetify ID
+    pub steam64_id: Steam64Id,
+    pub details: MatchDetailsResponse,
+    /// Watcher state after this match, to persist for resuming
+    pub state: WatchState,
+}
+
+/// Configuration of a watcher
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    interval: Duration,
+    jitter: f64,
+    max_backoff: Duration,
+    emit_existing: bool,
+    state: WatchState,
+}
+
+impl WatchOptions {
+    /// Poll every `interval`, with 10% jitter
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            jitter: 0.1,
+            max_backoff: DEFAULT_MAX_BACKOFF.max(interval),
+            emit_existing: false,
+            state: WatchState::default(),
+        }
+    }
+
+    /// Randomize every delay by up to `fraction` of itself in either
+    /// direction (clamped to 0.0 - 1.0)
+    pub fn jitter(mut self, fraction: f64) -> Self {
+        self.jitter = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Upper bound for the delay between polls while failing or rate limited
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Emit the matches found by the first poll instead of only recording
+    /// them as seen (has no effect when resuming a primed state)
+    ///
+    /// Only used by match watchers.
+    pub fn emit_existing(mut self, emit: bool) -> Self {
+        self.emit_existing = emit;
+        self
+    }
+
+    /// Continue from a previously persisted state
+    ///
+    /// Only used by match watchers; profile watchers take a baseline profile
+    /// instead.
+    pub fn resume(mut self, state: WatchState) -> Self {
+        self.state = state;
+        self
+    }
+}
+
+/// Poll timing with jitter and backoff, shared by all watchers
+struct Schedule {
+    interval: Duration,
+    jitter: f64,
+    max_backoff: Duration,
+    next_poll: Instant,
+    failures: u32,
+}
+
+impl Schedule {
+    fn new(options: &WatchOptions, first_poll: Instant) -> Self {
+        Self {
+            interval: options.interval,
+            jitter: options.jitter,
+            max_backoff: options.max_backoff,
+            next_poll: first_poll,
+            failures: 0,
+        }
+    }
+
+    async fn wait(&self) {
+        tokio::time::sleep_until(self.next_poll.into()).await;
+    }
+
+    fn succeeded(&mut self) {
+        self.failures = 0;
+        self.schedule(self.interval);
+    }
+
+    fn rate_limited(&mut self, retry_after: Option<Duration>) {
+        self.failures += 1;
+        self.schedule(retry_after.unwrap_or_default().max(self.backoff()));
+    }
+
+    fn failed(&mut self) {
+        self.failures += 1;
+        self.schedule(self.backoff());
+    }
+
+    /// Exponential backoff based on the number of consecutive failures
+    fn backoff(&self) -> Duration {
+        let factor = 2u32.saturating_pow(self.failures.min(16));
+        self.interval.saturating_mul(factor).min(self.max_backoff)
+    }
+
+    fn schedule(&mut self, delay: Duration) {
+        self.next_poll = Instant::now() + jittered(delay, self.jitter);
+    }
+}
+
+struct Watcher {
+    client: Client,
+    id: Id,
+    schedule: Schedule,
+    state: WatchState,
+    emit_existing: bool,
+    pending: VecDeque<(Steam64Id, MatchDetailsResponse)>,
+    polled: bool,
+}
+
+impl Watcher {
+    fn new(client: Client, id: Id, options: WatchOptions, first_poll: Instant) -> Self {
+        Self {
+            client,
+            id,
+            schedule: Schedule::new(&options, first_poll),
+            state: options.state,
+            emit_existing: options.emit_existing,
+            pending: VecDeque::new(),
+            polled: false,
+        }
+    }
+
+    fn into_stream(self) -> impl Stream<Item = Result<WatchedMatch, Error>> + Send + 'static {
+        stream::unfold(self, |mut watcher| async move {
+            let item = watcher.next().await;
+            Some((item, watcher))
+        })
+    }
+
+    async fn next(&mut self) -> Result<WatchedMatch, Error> {
+        loop {
+            if let Some((steam64_id, details)) = self.pending.pop_front() {
+                self.state.record(&details);
+                return Ok(WatchedMatch {
+                    player: self.id.clone(),
+                    steam64_id,
+                    details,
+                    state: self.state.clone(),
+                });
+            }
+
+            self.schedule.wait().await;
+
+            match self.poll().await {
+                Ok((steam64_id, matches)) => {
+                    self.schedule.succeeded();
+                    self.enqueue(steam64_id, matches);
+                }
+                Err(Error::RateLimited(retry_after)) => self.schedule.rate_limited(retry_after),
+                Err(e) => {
+                    self.schedule.failed();
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// Fetch the match history and the player's Steam64 ID, which the client
+    /// only has to resolve on the first poll of a Leetify ID
+    async fn poll(&self) -> Result<(Steam64Id, Vec<MatchDetailsResponse>), Error> {
+        let steam64_id = self.client.resolve_steam64_id(self.id.clone()).await?;
+        let matches = self.client.get_profile_matches(self.id.clone()).await?;
+        Ok((steam64_id, matches))
+    }
+
+    fn enqueue(&mut self, steam64_id: Steam64Id, mut matches: Vec<MatchDetailsResponse>) {
+        let state = &mut self.state;
+        let mut ids = HashSet::new();
+        matches.retain(|m| state.is_new(m) && ids.insert(m.id.clone()));
+        matches.sort_by_key(|m| m.finished_at);
+
+        let first_poll = !std::mem::replace(&mut self.polled, true);
+        if first_poll && !state.is_primed() && !self.emit_existing {
+            for details in &matches {
+                state.record(details);
+            }
+            return;
+        }
+        self.pending
+            .extend(matches.into_iter().map(|m| (steam64_id.clone(), m)));
+    }
+}
+
+/// A notable change in a watched player's profile
+#[derive(Debug, Clone)]
+pub struct ProfileEvent {
+    /// The watched player
+    pub player: Id,
+    pub change: ProfileChange,
+    /// The profile the change was detected in, to persist as the baseline
+    /// for resuming
+    pub profile: ProfileResponse,
+}
+
+struct ProfileWatcher {
+    client: Client,
+    id: Id,
+    schedule: Schedule,
+    thresholds: ChangeThresholds,
+    baseline: Option<ProfileResponse>,
+    pending: VecDeque<ProfileEvent>,
+}
+
+impl ProfileWatcher {
+    fn into_stream(self) -> impl Stream<Item = Result<ProfileEvent, Error>> + Send + 'static {
+        stream::unfold(self, |mut watcher| async move {
+            let item = watcher.next().await;
+            Some((item, watcher))
+        })
+    }
+
+    async fn next(&mut self) -> Result<ProfileEvent, Error> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(event);
+            }
+
+            self.schedule.wait().await;
+
+            match self.client.get_profile(self.id.clone()).await {
+                Ok(profile) => {
+                    self.schedule.succeeded();
+                    self.compare(profile);
+                }
+                Err(Error::RateLimited(retry_after)) => self.schedule.rate_limited(retry_after),
+                Err(e) => {
+                    self.schedule.failed();
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    fn compare(&mut self, profile: ProfileResponse) {
+        if let Some(baseline) = &self.baseline {
+            let diff = ProfileDiff::between(baseline, &profile, &self.thresholds);
+            self.pending
+                .extend(diff.changes.into_iter().map(|change| ProfileEvent {
+                    player: self.id.clone(),
+                    change,
+                    profile: profile.clone(),
+                }));
+        }
+        self.baseline = Some(profile);
+    }
+}
+
+/// Randomize `delay` by up to `fraction` of itself in either direction
+fn jittered(delay: Duration, fraction: f64) -> Duration {
+    let offset = (random_unit() * 2.0 - 1.0) * fraction;
+    delay.mul_f64((1.0 + offset).max(0.0))
+}
+
+/// Random number in `[0, 1)` from the standard library's hasher seeds
+fn random_unit() -> f64 {
+    let bits = RandomState::new().hash_one(Instant::now());
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+impl Player {
+    /// Watch the player for newly finished matches, polling every `interval`
+    ///
+    /// The first poll only records the existing match history; afterwards
+    /// every new match is emitted once, oldest first. Rate limiting is
+    /// handled by backing off; other errors are yielded and polling
+    /// continues.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Id};
+    /// use futures_util::StreamExt;
+    /// use std::time::Duration;
+    ///
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let player = client.player(Id::Steam64("76561198283431555".into()));
+    ///
+    /// let mut matches = Box::pin(player.watch(Duration::from_secs(60)));
+    /// while let Some(new_match) = matches.next().await {
+    ///     let new_match = new_match?;
+    ///     println!("New match on {}", new_match.details.map_name);
+    ///     // persist `new_match.state` to resume later
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn watch(
+        &self,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<WatchedMatch, Error>> + Send + 'static {
+        self.watch_with(WatchOptions::new(interval))
+    }
+
+    /// Watch the player for newly finished matches with custom options
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Id};
+    /// # use leetify::watch::{WatchOptions, WatchState};
+    /// # use std::time::Duration;
+    /// # fn load_state() -> WatchState { WatchState::default() }
+    /// let client = Client::new();
+    /// let player = client.player(Id::Steam64("76561198283431555".into()));
+    ///
+    /// let matches = player.watch_with(
+    ///     WatchOptions::new(Duration::from_secs(60))
+    ///         .jitter(0.2)
+    ///         .resume(load_state()),
+    /// );
+    /// ```
+    pub fn watch_with(
+        &self,
+        options: WatchOptions,
+    ) -> impl Stream<Item = Result<WatchedMatch, Error>> + Send + 'static {
+        Watcher::new(
+            self.client().clone(),
+            self.id().clone(),
+            options,
+            Instant::now(),
+        )
+        .into_stream()
+    }
+
+    /// Watch the player's profile for rank changes, new bans and rating
+    /// swings, polling every `interval`
+    ///
+    /// The first poll sets the baseline; afterwards every notable change
+    /// compared to the previous poll is emitted.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Id};
+    /// use futures_util::StreamExt;
+    /// use leetify::changes::{ChangeThresholds, ProfileChange};
+    /// use std::time::Duration;
+    ///
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let player = client.player(Id::Steam64("76561198283431555".into()));
+    ///
+    /// let mut events = Box::pin(
+    ///     player.watch_profile(Duration::from_secs(300), ChangeThresholds::default()),
+    /// );
+    /// while let Some(event) = events.next().await {
+    ///     if let ProfileChange::NewBan(ban) = event?.change {
+    ///         println!("Banned on {}", ban.platform);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn watch_profile(
+        &self,
+        interval: Duration,
+        thresholds: ChangeThresholds,
+    ) -> impl Stream<Item = Result<ProfileEvent, Error>> + Send + 'static {
+        self.watch_profile_with(WatchOptions::new(interval), thresholds, None)
+    }
+
+    /// Watch the player's profile with custom options, optionally comparing
+    /// the first poll against a persisted `baseline` profile
+    pub fn watch_profile_with(
+        &self,
+        options: WatchOptions,
+        thresholds: ChangeThresholds,
+        baseline: Option<ProfileResponse>,
+    ) -> impl Stream<Item = Result<ProfileEvent, Error>> + Send + 'static {
+        ProfileWatcher {
+            client: self.client().clone(),
+            id: self.id().clone(),
+            schedule: Schedule::new(&options, Instant::now()),
+            thresholds,
+            baseline,
+            pending: VecDeque::new(),
+        }
+        .into_stream()
+    }
+}
+
+impl Client {
+    /// Watch several players for newly finished matches
+    ///
+    /// Each player is polled every `interval`; first polls are spread over
+    /// the interval so requests don't arrive in bursts.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use leetify::{Client, Id};
+    /// use futures_util::StreamExt;
+    /// use std::time::Duration;
+    ///
+    /// # async fn example() -> Result<(), leetify::Error> {
+    /// let client = Client::new();
+    /// let ids = ["76561198283431555", "76561198000000000"];
+    ///
+    /// let mut matches = Box::pin(client.watch_players(ids, Duration::from_secs(60)));
+    /// while let Some(new_match) = matches.next().await {
+    ///     let new_match = new_match?;
+    ///     println!("{:?} finished a match", new_match.player);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn watch_players<I>(
+        &self,
+        ids: impl IntoIterator<Item = I>,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<WatchedMatch, Error>> + Send + 'static
+    where
+        I: Into<Id>,
+    {
+        self.watch_players_with(
+            ids.into_iter()
+                .map(move |id| (id.into(), WatchOptions::new(interval))),
+        )
+    }
+
+    /// Watch several players, each with its own options (e.g. resumed state)
+    pub fn watch_players_with(
+        &self,
+        players: impl IntoIterator<Item = (Id, WatchOptions)>,
+    ) -> impl Stream<Item = Result<WatchedMatch, Error>> + Send + 'static {
+        let now = Instant::now();
+        let watchers = players.into_iter().map(|(id, options)| {
+            let first_poll = now + options.interval.mul_f64(random_unit());
+            Box::pin(Watcher::new(self.clone(), id, options, first_poll).into_stream())
+        });
+        stream::select_all(watchers)
+    }
+
+    /// Watch several players' profiles for notable changes
+    ///
+    /// First polls are spread over the interval, as with
+    /// [`watch_players`](Self::watch_players).
+    pub fn watch_profiles<I>(
+        &self,
+        ids: impl IntoIterator<Item = I>,
+        interval: Duration,
+        thresholds: ChangeThresholds,
+    ) -> impl Stream<Item = Result<ProfileEvent, Error>> + Send + 'static
+    where
+        I: Into<Id>,
+    {
+        let options = WatchOptions::new(interval);
+        let now = Instant::now();
+        let watchers = ids.into_iter().map(|id| {
+            let first_poll = now + interval.mul_f64(random_unit());
+            Box::pin(
+                ProfileWatcher {
+                    client: self.clone(),
+                    id: id.into(),
+                    schedule: Schedule::new(&options, first_poll),
+                    thresholds,
+                    baseline: None,
+                    pending: VecDeque::new(),
+                }
+                .into_stream(),
+            )
+        });
+        stream::select_all(watchers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(id: &str, minute: i64) -> MatchDetailsResponse {
+        MatchDetailsResponse {
+            id: id.to_string(),
+            finished_at: DateTime::from_timestamp(minute * 60, 0).unwrap(),
+            ..Default::default()
+        }
+    }
+
+    fn new_watcher(options: WatchOptions) -> Watcher {
+        Watcher::new(
+            Client::new(),
+            Id::Steam64("1".into()),
+            options,
+            Instant::now(),
+        )
+    }
+
+    #[test]
+    fn test_first_poll_primes_state() {
+        let mut watcher = new_watcher(WatchOptions::new(Duration::from_secs(60)));
+        watcher.enqueue("1".into(), vec![game("b", 2), game("a", 1)]);
+        assert!(watcher.pending.is_empty());
+        assert_eq!(watcher.state.last_match_id.as_deref(), Some("b"));
+
+        watcher.enqueue(
+            "1".into(),
+            vec![game("c", 3), game("b", 2), game("a", 1), game("c", 3)],
+        );
+        let pending: Vec<_> = watcher.pending.iter().map(|(_, m)| m.id.as_str()).collect();
+        assert_eq!(pending, vec!["c"]);
+    }
+
+    #[tokio::test]
+    async fn test_watched_match_carries_steam64_id() {
+        let client = Client::new();
+        let leetify_id: crate::types::LeetifyId = "5ea07280-2399-4c7e-88ab-f2f7db0c449f".into();
+        client.remember_ids("76561198283431555".into(), leetify_id.clone());
+
+        let mut watcher = Watcher::new(
+            client,
+            Id::Leetify(leetify_id),
+            WatchOptions::new(Duration::from_secs(60)).emit_existing(true),
+            Instant::now(),
+        );
+        let steam64_id = watcher
+            .client
+            .resolve_steam64_id(watcher.id.clone())
+            .await
+            .unwrap();
+        watcher.enqueue(steam64_id, vec![game("a", 1)]);
+        let watched = watcher.next().await.unwrap();
+        assert_eq!(watched.steam64_id.as_ref(), "76561198283431555");
+    }
+
+    #[test]
+    fn test_empty_history_primes_state() {
+        let mut watcher = new_watcher(WatchOptions::new(Duration::from_secs(60)));
+        watcher.enqueue("1".into(), Vec::new());
+        watcher.enqueue("1".into(), vec![game("a", 1)]);
+        assert_eq!(watcher.pending.len(), 1);
+    }
+
+    #[test]
+    fn test_resume_and_emit_existing() {
+        let mut state = WatchState::default();
+        state.record(&game("a", 1));
+
+        let mut watcher = new_watcher(WatchOptions::new(Duration::from_secs(60)).resume(state));
+        watcher.enqueue("1".into(), vec![game("a", 1), game("b", 2)]);
+        assert_eq!(watcher.pending.len(), 1);
+
+        let mut watcher =
+            new_watcher(WatchOptions::new(Duration::from_secs(60)).emit_existing(true));
+        watcher.enqueue("1".into(), vec![game("b", 2), game("a", 1)]);
+        let pending: Vec<_> = watcher.pending.iter().map(|(_, m)| m.id.as_str()).collect();
+        assert_eq!(pending, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_backoff_and_jitter() {
+        let mut watcher = new_watcher(
+            WatchOptions::new(Duration::from_secs(60)).max_backoff(Duration::from_secs(300)),
+        );
+        watcher.schedule.failures = 1;
+        assert_eq!(watcher.schedule.backoff(), Duration::from_secs(120));
+        watcher.schedule.failures = 10;
+        assert_eq!(watcher.schedule.backoff(), Duration::from_secs(300));
+
+        for _ in 0..100 {
+            let delay = jittered(Duration::from_secs(100), 0.1);
+            assert!(delay >= Duration::from_secs(90) && delay <= Duration::from_secs(110));
+        }
+    }
+
+    #[test]
+    fn test_profile_watcher_emits_changes() {
+        let mut watcher = ProfileWatcher {
+            client: Client::new(),
+            id: Id::Steam64("1".into()),
+            schedule: Schedule::new(&WatchOptions::new(Duration::from_secs(60)), Instant::now()),
+            thresholds: ChangeThresholds::default(),
+            baseline: None,
+            pending: VecDeque::new(),
+        };
+        let profile = |faceit| ProfileResponse {
+            ranks: crate::types::Ranks {
+                faceit: Some(faceit),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        watcher.compare(profile(5));
+        assert!(watcher.pending.is_empty());
+        watcher.compare(profile(5));
+        assert!(watcher.pending.is_empty());
+        watcher.compare(profile(6));
+        assert_eq!(
+            watcher.pending[0].change,
+            ProfileChange::FaceitLevel {
+                from: Some(5),
+                to: Some(6),
+            }
+        );
+    }
+
+    #[test]
+    fn test_late_matches_are_new() {
+        let mut state = WatchState::default();
+        state.record(&game("b", 10));
+
+        assert!(!state.is_new(&game("b", 10)));
+        assert!(state.is_new(&game("a", 5)));
+        assert!(!state.is_new(&game("old", 10 - LOOKBACK_DAYS * 24 * 60 - 1)));
+
+        state.record(&game("a", 5));
+        assert!(!state.is_new(&game("a", 5)));
+        assert_eq!(state.last_match_id.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_seen_is_bounded() {
+        let mut state = WatchState::default();
+        for i in 0..(MAX_SEEN as i64 + 10) {
+            state.record(&game(&i.to_string(), i));
+        }
+        assert_eq!(state.seen.len(), MAX_SEEN);
+        assert_eq!(state.last_match_id.as_deref(), Some("109"));
+    }
+}
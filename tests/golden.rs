@@ -0,0 +1,392 @@
+//! Golden-file tests for response deserialization
+//!
+//! `tests/golden` holds one response of every endpoint. The files checked in
+//! are synthetic, built from the `builders` defaults, until they are replaced
+//! by anonymized captures of the live API with the ignored `record_golden`
+//! test (see its docs).
+//!
+//! Each file must deserialize, and every field the types model must survive
+//! serializing the result again. Fields the types don't model are listed in
+//! `tests/golden/unknown_fields.txt` instead, so a field the API adds or
+//! renames shows up as a change to that list.
+
+use chrono::DateTime;
+use leetify::{DataSource, MatchDetailsResponse, ProfileResponse};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Steam64 ID of the player the golden files were recorded for
+const PLAYER: &str = "76561197960265728";
+
+/// Leetify ID of that player
+const PLAYER_LEETIFY_ID: &str = "00000000-0000-4000-a000-000000000000";
+
+/// Golden files, in the order `record_golden` requests them
+const FILES: [&str; 4] = [
+    "profile.json",
+    "profile_matches.json",
+    "match_by_game_id.json",
+    "match_by_data_source.json",
+];
+
+fn golden_path(name: &str) -> String {
+    format!("{}/tests/golden/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn golden(name: &str) -> String {
+    let path = golden_path(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+/// Make numbers comparable regardless of whether they were written as
+/// integers or floats, and timestamps regardless of their precision and
+/// offset
+fn normalize(value: Value) -> Value {
+    match value {
+        Value::Number(n) => n.as_f64().map_or(Value::Number(n), Value::from),
+        Value::String(s) => match DateTime::parse_from_rfc3339(&s) {
+            Ok(time) => Value::from(time.timestamp_nanos_opt()),
+            Err(_) => Value::String(s),
+        },
+        Value::Array(values) => Value::Array(values.into_iter().map(normalize).collect()),
+        Value::Object(map) => {
+            Value::Object(map.into_iter().map(|(k, v)| (k, normalize(v))).collect())
+        }
+        other => other,
+    }
+}
+
+/// Check that every field of `golden` that `serialized` has too kept its
+/// value, and collect the paths of the fields it lacks
+fn compare(path: &str, golden: &Value, serialized: &Value, unknown: &mut BTreeSet<String>) {
+    match (golden, serialized) {
+        (Value::Object(golden), Value::Object(serialized)) => {
+            for (key, value) in golden {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                match serialized.get(key) {
+                    Some(serialized) => compare(&path, value, serialized, unknown),
+                    None => {
+                        unknown.insert(path);
+                    }
+                }
+            }
+        }
+        (Value::Array(golden), Value::Array(serialized)) => {
+            assert_eq!(golden.len(), serialized.len(), "{}", path);
+            let path = format!("{}[]", path);
+            for (golden, serialized) in golden.iter().zip(serialized) {
+                compare(&path, golden, serialized, unknown);
+            }
+        }
+        _ => assert_eq!(
+            normalize(serialized.clone()),
+            normalize(golden.clone()),
+            "{}",
+            path
+        ),
+    }
+}
+
+/// Deserialize a golden file, check that serializing it again keeps every
+/// field the type models, and return the paths of the fields it doesn't
+fn round_trip<T: Serialize + DeserializeOwned>(name: &str) -> (T, BTreeSet<String>) {
+    let json = golden(name);
+    let parsed: T = serde_json::from_str(&json).unwrap_or_else(|e| panic!("{}: {}", name, e));
+
+    let serialized = serde_json::to_value(&parsed).unwrap();
+    let expected: Value = serde_json::from_str(&json).unwrap();
+    let mut unknown = BTreeSet::new();
+    compare(name, &expected, &serialized, &mut unknown);
+
+    let reparsed: T = serde_json::from_value(serialized.clone()).unwrap();
+    assert_eq!(
+        serde_json::to_value(&reparsed).unwrap(),
+        serialized,
+        "{}",
+        name
+    );
+    (parsed, unknown)
+}
+
+#[test]
+fn test_profile() {
+    let (profile, _) = round_trip::<ProfileResponse>("profile.json");
+
+    assert_eq!(profile.steam64_id, PLAYER);
+    assert_eq!(profile.id.as_deref(), Some(PLAYER_LEETIFY_ID));
+    assert!(!profile.recent_matches.is_empty());
+}
+
+#[test]
+fn test_profile_matches() {
+    let (matches, _) = round_trip::<Vec<MatchDetailsResponse>>("profile_matches.json");
+
+    assert!(!matches.is_empty());
+    for details in &matches {
+        assert!(details.player_stats(PLAYER).is_some());
+    }
+}
+
+#[test]
+fn test_match_by_game_id() {
+    let (profile, _) = round_trip::<ProfileResponse>("profile.json");
+    let (details, _) = round_trip::<MatchDetailsResponse>("match_by_game_id.json");
+
+    assert_eq!(details.id, profile.recent_matches[0].id);
+    assert!(details.player_stats(PLAYER).is_some());
+    for player in &details.stats {
+        let team = details
+            .team_scores
+            .iter()
+            .find(|team| team.team_number == player.initial_team_number)
+            .unwrap();
+        assert_eq!(player.rounds_won, team.score);
+    }
+}
+
+#[test]
+fn test_match_by_data_source() {
+    let (matches, _) = round_trip::<Vec<MatchDetailsResponse>>("profile_matches.json");
+    let (details, _) = round_trip::<MatchDetailsResponse>("match_by_data_source.json");
+
+    assert!(matches.iter().any(|m| m.id == details.id));
+    let data_source = DataSource::from(details.data_source.as_str());
+    assert_eq!(
+        serde_json::to_value(&data_source).unwrap(),
+        details.data_source
+    );
+}
+
+/// Fields the types don't model must match `tests/golden/unknown_fields.txt`
+#[test]
+fn test_unknown_fields() {
+    let mut unknown = BTreeSet::new();
+    unknown.extend(round_trip::<ProfileResponse>(FILES[0]).1);
+    unknown.extend(round_trip::<Vec<MatchDetailsResponse>>(FILES[1]).1);
+    unknown.extend(round_trip::<MatchDetailsResponse>(FILES[2]).1);
+    unknown.extend(round_trip::<MatchDetailsResponse>(FILES[3]).1);
+
+    let listed: BTreeSet<String> = golden("unknown_fields.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect();
+    assert!(
+        unknown == listed,
+        "fields not modeled by the types changed; add them to the types or list them in \
+         tests/golden/unknown_fields.txt\nnew: {:?}\ngone: {:?}",
+        unknown.difference(&listed).collect::<Vec<_>>(),
+        listed.difference(&unknown).collect::<Vec<_>>(),
+    );
+}
+
+/// The golden files served through the client's endpoints
+#[cfg(feature = "vcr")]
+#[tokio::test]
+async fn test_client_endpoints() {
+    use leetify::Client;
+    use leetify::vcr::{Cassette, Interaction, RecordedRequest, RecordedResponse, Vcr};
+
+    let by_game_id: MatchDetailsResponse =
+        serde_json::from_str(&golden("match_by_game_id.json")).unwrap();
+    let by_data_source: MatchDetailsResponse =
+        serde_json::from_str(&golden("match_by_data_source.json")).unwrap();
+    let data_source = DataSource::from(by_data_source.data_source.as_str());
+
+    let interaction = |path: String, query: Option<&str>, name: &str| Interaction {
+        request: RecordedRequest {
+            method: "GET".to_string(),
+            path,
+            query: query.map(str::to_string),
+            headers: Default::default(),
+        },
+        response: RecordedResponse {
+            status: 200,
+            retry_after: None,
+            body: golden(name),
+        },
+    };
+    let query = format!("steam64_id={}", PLAYER);
+    let path = std::env::temp_dir().join(format!("leetify-golden-{}.json", std::process::id()));
+    Cassette {
+        interactions: vec![
+            interaction("/v3/profile".to_string(), Some(&query), FILES[0]),
+            interaction("/v3/profile/matches".to_string(), Some(&query), FILES[1]),
+            interaction(format!("/v2/matches/{}", by_game_id.id), None, FILES[2]),
+            interaction(
+                format!(
+                    "/v2/matches/{}/{}",
+                    data_source.as_str(),
+                    by_data_source.data_source_match_id
+                ),
+                None,
+                FILES[3],
+            ),
+        ],
+    }
+    .save(&path)
+    .unwrap();
+    let vcr = Vcr::strict(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let client = Client::builder()
+        .base_url("http://127.0.0.1:9")
+        .vcr(vcr.clone())
+        .build()
+        .unwrap();
+    let profile = client.get_profile(PLAYER).await.unwrap();
+    let matches = client.get_profile_matches(PLAYER).await.unwrap();
+    let game = client
+        .get_match_by_game_id(by_game_id.id.clone())
+        .await
+        .unwrap();
+    let sourced = client
+        .get_match_by_data_source(data_source, &by_data_source.data_source_match_id)
+        .await
+        .unwrap();
+
+    assert!(vcr.unplayed().is_empty());
+    assert_eq!(profile.recent_matches[0].id, game.id);
+    assert!(matches.iter().any(|m| m.id == sourced.id));
+}
+
+/// Replaces player IDs and names with placeholders, consistently across
+/// files
+#[cfg(feature = "vcr")]
+#[derive(Default)]
+struct Anonymizer {
+    replacements: std::collections::HashMap<String, String>,
+    players: usize,
+    names: usize,
+}
+
+#[cfg(feature = "vcr")]
+impl Anonymizer {
+    fn steam64_id(&mut self, id: &str) -> String {
+        if let Some(replacement) = self.replacements.get(id) {
+            return replacement.clone();
+        }
+        let replacement = (PLAYER.parse::<u64>().unwrap() + self.players as u64).to_string();
+        self.players += 1;
+        self.replacements
+            .insert(id.to_string(), replacement.clone());
+        replacement
+    }
+
+    fn name(&mut self, name: &str) -> String {
+        if let Some(replacement) = self.replacements.get(name) {
+            return replacement.clone();
+        }
+        let replacement = format!("player{}", self.names);
+        self.names += 1;
+        self.replacements
+            .insert(name.to_string(), replacement.clone());
+        replacement
+    }
+
+    fn scrub(&mut self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    match (key.as_str(), &*value) {
+                        ("steam64_id", Value::String(id)) => *value = self.steam64_id(id).into(),
+                        ("name" | "platform_nickname", Value::String(name)) => {
+                            *value = self.name(name).into()
+                        }
+                        _ => self.scrub(value),
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|value| self.scrub(value)),
+            Value::String(s) => {
+                if let Some(replacement) = self.replacements.get(s.as_str()) {
+                    *s = replacement.clone();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Record the golden files from the live API
+///
+/// Requests the profile and match history of the player in
+/// `LEETIFY_GOLDEN_PLAYER` (a Steam64 ID), their most recent match by game ID
+/// and a match of their history by data source, preferring one that is not
+/// from Matchmaking. Player IDs, the Leetify ID and names are replaced by
+/// placeholders before the files are written; match IDs are kept.
+///
+/// ```bash
+/// LEETIFY_API_KEY=... LEETIFY_GOLDEN_PLAYER=7656... \
+///     cargo test --features vcr --test golden -- --ignored record_golden
+/// ```
+///
+/// Review the diff before committing: the anonymization only knows about the
+/// fields listed above.
+#[cfg(feature = "vcr")]
+#[tokio::test]
+#[ignore = "needs an API key and network access"]
+async fn record_golden() {
+    use leetify::Client;
+    use leetify::vcr::{Cassette, Vcr};
+
+    let api_key = std::env::var("LEETIFY_API_KEY").expect("LEETIFY_API_KEY is not set");
+    let player = std::env::var("LEETIFY_GOLDEN_PLAYER").expect("LEETIFY_GOLDEN_PLAYER is not set");
+    let path =
+        std::env::temp_dir().join(format!("leetify-golden-record-{}.json", std::process::id()));
+    let vcr = Vcr::record(&path);
+    let client = Client::builder()
+        .api_key(api_key)
+        .vcr(vcr.clone())
+        .build()
+        .unwrap();
+
+    let profile = client.get_profile(player.as_str()).await.unwrap();
+    let matches = client.get_profile_matches(player.as_str()).await.unwrap();
+    let recent = profile
+        .recent_matches
+        .first()
+        .expect("the player has no recent matches");
+    client
+        .get_match_by_game_id(recent.id.clone())
+        .await
+        .unwrap();
+    let sourced = matches
+        .iter()
+        .find(|m| DataSource::from(m.data_source.as_str()) != DataSource::Matchmaking)
+        .or(matches.first())
+        .expect("the player has no match history");
+    client
+        .get_match_by_data_source(
+            DataSource::from(sourced.data_source.as_str()),
+            &sourced.data_source_match_id,
+        )
+        .await
+        .unwrap();
+    vcr.save().unwrap();
+    let cassette = Cassette::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut anonymizer = Anonymizer::default();
+    anonymizer.steam64_id(&player);
+    if let Some(id) = &profile.id {
+        anonymizer
+            .replacements
+            .insert(id.clone(), PLAYER_LEETIFY_ID.to_string());
+    }
+    assert_eq!(cassette.interactions.len(), FILES.len());
+    for (interaction, name) in cassette.interactions.iter().zip(FILES) {
+        let mut body: Value = serde_json::from_str(&interaction.response.body).unwrap();
+        anonymizer.scrub(&mut body);
+        let mut json = serde_json::to_string_pretty(&body).unwrap();
+        json.push('\n');
+        std::fs::write(golden_path(name), json).unwrap();
+    }
+}